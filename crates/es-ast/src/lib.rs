@@ -1,26 +1,54 @@
-#[derive(Debug)]
+pub mod print;
+pub mod span;
+pub mod visit;
+pub use span::Span;
+pub use visit::Visitor;
+pub use visit::VisitorMut;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum Statement {
     Loop(Loop),
     If(If),
     Assign(Assign),
     Block(Block),
+    Def(Def),
+    Return(Return),
     Expression(Expression),
 }
 
-#[derive(Debug)]
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Loop(stmt) => stmt.span(),
+            Statement::If(stmt) => stmt.span(),
+            Statement::Assign(stmt) => stmt.span(),
+            Statement::Block(stmt) => stmt.span(),
+            Statement::Def(stmt) => stmt.span(),
+            Statement::Return(stmt) => stmt.span(),
+            Statement::Expression(expr) => expr.span().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Loop {
     comparison: Comparison,
     block: Block,
+    span: Span,
 }
 
 impl Loop {
     pub fn new(comparison: Comparison) -> Self {
         Self {
             comparison: comparison,
-            block: Block {
-                left: None,
-                right: None,
-            },
+            block: Block::new(),
+            span: Span::default(),
         }
     }
 
@@ -35,24 +63,42 @@ impl Loop {
     pub fn block(&self) -> &Block {
         &self.block
     }
+
+    pub fn comparison_mut(&mut self) -> &mut Comparison {
+        &mut self.comparison
+    }
+
+    pub fn block_mut(&mut self) -> &mut Block {
+        &mut self.block
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct If {
     comparison: Comparison,
     block: Block,
-    child: Option<Box<Self>>,
+    children: Vec<If>,
+    else_block: Option<Block>,
+    span: Span,
 }
 
 impl If {
     pub fn new(comparison: Comparison) -> Self {
         Self {
             comparison: comparison,
-            block: Block {
-                left: None,
-                right: None,
-            },
-            child: None,
+            block: Block::new(),
+            children: Vec::new(),
+            else_block: None,
+            span: Span::default(),
         }
     }
 
@@ -60,12 +106,13 @@ impl If {
         self.block = block;
     }
 
+    // children are the elif branches, in source order.
     pub fn insert_child(&mut self, child: If) {
-        if let Some(self_child) = self.child.as_mut() {
-            self_child.insert_child(child)
-        } else {
-            self.child = Some(Box::new(child))
-        }
+        self.children.push(child);
+    }
+
+    pub fn insert_else(&mut self, block: Block) {
+        self.else_block = Some(block);
     }
 
     pub fn comparison(&self) -> &Comparison {
@@ -76,18 +123,125 @@ impl If {
         &self.block
     }
 
-    pub fn child(&self) -> Option<&If> {
-        match &self.child {
-            Some(child) => Some(&child),
-            None => None,
+    pub fn children(&self) -> &[If] {
+        &self.children
+    }
+
+    pub fn else_block(&self) -> Option<&Block> {
+        self.else_block.as_ref()
+    }
+
+    pub fn comparison_mut(&mut self) -> &mut Comparison {
+        &mut self.comparison
+    }
+
+    pub fn block_mut(&mut self) -> &mut Block {
+        &mut self.block
+    }
+
+    pub fn children_mut(&mut self) -> &mut [If] {
+        &mut self.children
+    }
+
+    pub fn else_block_mut(&mut self) -> Option<&mut Block> {
+        self.else_block.as_mut()
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Def {
+    name: String,
+    params: Vec<String>,
+    block: Block,
+    span: Span,
+}
+
+impl Def {
+    pub fn new(name: String, params: Vec<String>) -> Self {
+        Self {
+            name: name,
+            params: params,
+            block: Block::new(),
+            span: Span::default(),
         }
     }
+
+    pub fn insert_block(&mut self, block: Block) {
+        self.block = block;
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn params(&self) -> &[String] {
+        &self.params
+    }
+
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+
+    pub fn block_mut(&mut self) -> &mut Block {
+        &mut self.block
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Return {
+    expr: Option<Expression>,
+    span: Span,
+}
+
+impl Return {
+    pub fn new(expr: Option<Expression>) -> Self {
+        Self {
+            expr: expr,
+            span: Span::default(),
+        }
+    }
+
+    pub fn expr(&self) -> Option<&Expression> {
+        self.expr.as_ref()
+    }
+
+    pub fn expr_mut(&mut self) -> Option<&mut Expression> {
+        self.expr.as_mut()
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Assign {
     identify: Expression,
     expr: Expression,
+    span: Span,
 }
 
 impl Assign {
@@ -95,6 +249,7 @@ impl Assign {
         Self {
             identify: identify,
             expr: expr,
+            span: Span::default(),
         }
     }
 
@@ -105,149 +260,256 @@ impl Assign {
     pub fn expr(&self) -> &Expression {
         &self.expr
     }
+
+    pub fn identify_mut(&mut self) -> &mut Expression {
+        &mut self.identify
+    }
+
+    pub fn expr_mut(&mut self) -> &mut Expression {
+        &mut self.expr
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Block {
-    left: Option<Box<Statement>>,
-    right: Option<Box<Statement>>,
+    statements: Vec<Statement>,
+    span: Span,
 }
 
 impl Block {
     pub fn new() -> Self {
         Self {
-            left: None,
-            right: None,
+            statements: Vec::new(),
+            span: Span::default(),
         }
     }
 
-    pub fn insert_left(&mut self, statement: Statement) {
-        self.left = Some(Box::new(statement));
+    pub fn insert(&mut self, statement: Statement) {
+        self.statements.push(statement);
     }
 
-    pub fn insert_right(&mut self, statement: Statement) {
-        self.right = Some(Box::new(statement));
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
     }
 
-    pub fn left(&self) -> Option<&Statement> {
-        match &self.left {
-            Some(left) => Some(&left),
-            None => None,
-        }
+    pub fn statements_mut(&mut self) -> &mut Vec<Statement> {
+        &mut self.statements
     }
 
-    pub fn right(&self) -> Option<&Statement> {
-        match &self.right {
-            Some(right) => Some(&right),
-            None => None,
+    pub fn iter(&self) -> std::slice::Iter<'_, Statement> {
+        self.statements.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl From<Vec<Statement>> for Block {
+    fn from(statements: Vec<Statement>) -> Self {
+        Self {
+            statements: statements,
+            span: Span::default(),
         }
     }
 }
 
-#[derive(Debug)]
+impl IntoIterator for Block {
+    type Item = Statement;
+    type IntoIter = std::vec::IntoIter<Statement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.statements.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Block {
+    type Item = &'a Statement;
+    type IntoIter = std::slice::Iter<'a, Statement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.statements.iter()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum Expression {
     String(String),
     Variable(String),
     Number(isize),
     Background(bool),
     Boolean(bool),
+    #[cfg_attr(feature = "serde", serde(rename = "fd"))]
     FD(u32),
     Command(Command),
     Redirect(Redirect),
     Pipe(Pipe),
+    Logical(Logical),
+    Time(Time),
     Comparison(Comparison),
 }
 
-#[derive(Debug)]
+impl Expression {
+    // literals do not carry a location, only the nodes built around them do.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Expression::Command(expr) => Some(expr.span()),
+            Expression::Redirect(expr) => Some(expr.span()),
+            Expression::Pipe(expr) => Some(expr.span()),
+            Expression::Logical(expr) => Some(expr.span()),
+            Expression::Time(expr) => Some(expr.span()),
+            Expression::Comparison(expr) => Some(expr.span()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Command {
     prefix: Box<Expression>,
-    suffix: Option<CommandSuffix>,
+    suffix: CommandSuffix,
+    span: Span,
 }
 
 impl Command {
     pub fn new(prefix: Expression) -> Self {
         Self {
             prefix: Box::new(prefix),
-            suffix: None,
+            suffix: CommandSuffix::new(),
+            span: Span::default(),
         }
     }
 
-    // pub fn insert_suffix(&mut self, expr: Expression) {
-    //     if let Some(suffix) = self.suffix.as_mut() {
-    //         suffix.insert(expr);
-    //     } else {
-    //         let mut suffix = CommandSuffix::new();
-    //         suffix.insert(expr);
-    //         self.suffix = Some(suffix);
-    //     }
-    // }
-
-    pub fn insert_suffix(&mut self,suffix:CommandSuffix){
-        self.suffix = Some(suffix);
-        
+    pub fn insert_suffix(&mut self, suffix: CommandSuffix) {
+        self.suffix = suffix;
     }
 
     pub fn prefix(&self) -> &Expression {
         &self.prefix
     }
 
-    pub fn suffix(&mut self) -> Option<&CommandSuffix> {
-        match &self.suffix {
-            Some(suffix) => Some(suffix),
-            None => None,
-        }
+    pub fn suffix(&self) -> &CommandSuffix {
+        &self.suffix
+    }
+
+    pub fn prefix_mut(&mut self) -> &mut Expression {
+        &mut self.prefix
+    }
+
+    pub fn suffix_mut(&mut self) -> &mut CommandSuffix {
+        &mut self.suffix
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
     }
 }
 
-#[derive(Debug)]
+// arguments and redirects following the command name, in source order.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommandSuffix {
-    expr: Option<Box<Expression>>,
-    suffix: Option<Box<Self>>,
+    exprs: Vec<Expression>,
+    spans: Vec<Span>,
 }
 
 impl CommandSuffix {
     pub fn new() -> Self {
         Self {
-            expr: None,
-            suffix: None,
+            exprs: Vec::new(),
+            spans: Vec::new(),
         }
     }
 
     pub fn insert(&mut self, expr: Expression) {
-        if self.expr.is_none() {
-            self.expr = Some(Box::new(expr));
-        } else if self.suffix.is_none() {
-            self.suffix = Some(Box::new(CommandSuffix {
-                expr: Some(Box::new(expr)),
-                suffix: None,
-            }));
-        } else {
-            if let Some(suffix) = self.suffix.as_mut() {
-                suffix.insert(expr);
-            }
-        }
+        self.insert_with_span(expr, Span::default());
     }
 
-    pub fn expr(&self) -> Option<&Expression> {
-        match &self.expr {
-            Some(expr) => Some(&expr),
-            None => None,
-        }
+    // span is the location of expr itself, not of the whole suffix.
+    pub fn insert_with_span(&mut self, expr: Expression, span: Span) {
+        self.exprs.push(expr);
+        self.spans.push(span);
     }
 
-    pub fn suffix(&self) -> Option<&CommandSuffix> {
-        match &self.suffix {
-            Some(suffix) => Some(&suffix),
-            None => None,
+    pub fn exprs(&self) -> &[Expression] {
+        &self.exprs
+    }
+
+    pub fn exprs_mut(&mut self) -> &mut [Expression] {
+        &mut self.exprs
+    }
+
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Expression> {
+        self.exprs.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.exprs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exprs.is_empty()
+    }
+
+    pub fn span(&self) -> Span {
+        match (self.spans.first(), self.spans.last()) {
+            (Some(first), Some(last)) => first.to(last),
+            _ => Span::default(),
         }
     }
 }
 
-#[derive(Debug)]
+impl<'a> IntoIterator for &'a CommandSuffix {
+    type Item = &'a Expression;
+    type IntoIter = std::slice::Iter<'a, Expression>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.exprs.iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Redirect {
     kind: RedirectKind,
     left: Box<Expression>,
     right: Box<Expression>,
+    span: Span,
 }
 
 impl Redirect {
@@ -256,6 +518,7 @@ impl Redirect {
             kind: kind,
             left: Box::new(left),
             right: Box::new(right),
+            span: Span::default(),
         }
     }
 
@@ -270,18 +533,38 @@ impl Redirect {
     pub fn right(&self) -> &Expression {
         &self.right
     }
+
+    pub fn left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+
+    pub fn right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum RedirectKind {
     Write,
     Read,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pipe {
     left: Option<Box<Expression>>,
     right: Option<Box<Expression>>,
+    span: Span,
 }
 
 impl Pipe {
@@ -289,6 +572,7 @@ impl Pipe {
         Self {
             left: None,
             right: None,
+            span: Span::default(),
         }
     }
 
@@ -313,24 +597,251 @@ impl Pipe {
             None => None,
         }
     }
+
+    pub fn left_mut(&mut self) -> Option<&mut Expression> {
+        self.left.as_deref_mut()
+    }
+
+    pub fn right_mut(&mut self) -> Option<&mut Expression> {
+        self.right.as_deref_mut()
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+// a && b, a || b
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Logical {
+    kind: LogicalKind,
+    left: Box<Expression>,
+    right: Box<Expression>,
+    span: Span,
+}
+
+impl Logical {
+    pub fn new(kind: LogicalKind, left: Expression, right: Expression) -> Self {
+        Self {
+            kind: kind,
+            left: Box::new(left),
+            right: Box::new(right),
+            span: Span::default(),
+        }
+    }
+
+    pub fn kind(&self) -> &LogicalKind {
+        &self.kind
+    }
+
+    pub fn left(&self) -> &Expression {
+        &self.left
+    }
+
+    pub fn right(&self) -> &Expression {
+        &self.right
+    }
+
+    pub fn left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+
+    pub fn right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+// time pipeline, reports how long the pipeline took once it is done.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Time {
+    pipeline: Box<Expression>,
+    span: Span,
+}
+
+impl Time {
+    pub fn new(pipeline: Expression) -> Self {
+        Self {
+            pipeline: Box::new(pipeline),
+            span: Span::default(),
+        }
+    }
+
+    pub fn pipeline(&self) -> &Expression {
+        &self.pipeline
+    }
+
+    pub fn pipeline_mut(&mut self) -> &mut Expression {
+        &mut self.pipeline
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LogicalKind {
+    And,
+    Or,
 }
 
-#[derive(Debug)]
-pub enum Comparison {
-    Equal {
-        left: Box<Expression>,
-        right: Box<Expression>,
-    },
-    NotEqual {
-        left: Box<Expression>,
-        right: Box<Expression>,
-    },
-    Gt {
-        left: Box<Expression>,
-        right: Box<Expression>,
-    },
-    Lt {
-        left: Box<Expression>,
-        right: Box<Expression>,
-    },
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comparison {
+    kind: ComparisonKind,
+    left: Box<Expression>,
+    right: Box<Expression>,
+    span: Span,
+}
+
+impl Comparison {
+    pub fn new(kind: ComparisonKind, left: Expression, right: Expression) -> Self {
+        Self {
+            kind: kind,
+            left: Box::new(left),
+            right: Box::new(right),
+            span: Span::default(),
+        }
+    }
+
+    pub fn kind(&self) -> &ComparisonKind {
+        &self.kind
+    }
+
+    pub fn left(&self) -> &Expression {
+        &self.left
+    }
+
+    pub fn right(&self) -> &Expression {
+        &self.right
+    }
+
+    pub fn left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+
+    pub fn right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ComparisonKind {
+    Equal,
+    NotEqual,
+    Gt,
+    Lt,
+}
+
+// spans are left out of equality, so a tree compares equal to the tree parsed
+// from the same source printed again.
+impl PartialEq for Loop {
+    fn eq(&self, other: &Self) -> bool {
+        self.comparison == other.comparison && self.block == other.block
+    }
+}
+
+impl PartialEq for If {
+    fn eq(&self, other: &Self) -> bool {
+        self.comparison == other.comparison
+            && self.block == other.block
+            && self.children == other.children
+            && self.else_block == other.else_block
+    }
+}
+
+impl PartialEq for Def {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.params == other.params && self.block == other.block
+    }
+}
+
+impl PartialEq for Return {
+    fn eq(&self, other: &Self) -> bool {
+        self.expr == other.expr
+    }
+}
+
+impl PartialEq for Assign {
+    fn eq(&self, other: &Self) -> bool {
+        self.identify == other.identify && self.expr == other.expr
+    }
+}
+
+impl PartialEq for Block {
+    fn eq(&self, other: &Self) -> bool {
+        self.statements == other.statements
+    }
+}
+
+impl PartialEq for Command {
+    fn eq(&self, other: &Self) -> bool {
+        self.prefix == other.prefix && self.suffix == other.suffix
+    }
+}
+
+impl PartialEq for CommandSuffix {
+    fn eq(&self, other: &Self) -> bool {
+        self.exprs == other.exprs
+    }
+}
+
+impl PartialEq for Redirect {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.left == other.left && self.right == other.right
+    }
+}
+
+impl PartialEq for Pipe {
+    fn eq(&self, other: &Self) -> bool {
+        self.left == other.left && self.right == other.right
+    }
+}
+
+impl PartialEq for Logical {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.left == other.left && self.right == other.right
+    }
+}
+
+impl PartialEq for Time {
+    fn eq(&self, other: &Self) -> bool {
+        self.pipeline == other.pipeline
+    }
+}
+
+impl PartialEq for Comparison {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.left == other.left && self.right == other.right
+    }
 }
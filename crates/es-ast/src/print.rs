@@ -0,0 +1,305 @@
+use crate::*;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+const INDENT: &str = "    ";
+
+const KEYWORDS: [&str; 9] = [
+    "if", "elif", "else", "loop", "def", "return", "time", "true", "false",
+];
+
+// a string used as a command argument. quoted when the lexer would not read it
+// back as the same single word. a `"` inside a word is an ordinary character
+// and there are no escapes, so such a word is printed as written.
+pub fn quote_argument(string: &str) -> String {
+    let is_plain = string.is_empty() == false
+        && string.starts_with(['$', '&', '#', '!', '"']) == false
+        && string.contains(|ch: char| {
+            ch.is_whitespace()
+                || matches!(
+                    ch,
+                    ';' | ',' | '=' | '|' | '>' | '<' | '(' | ')' | '{' | '}' | '\\'
+                )
+        }) == false
+        && match string.parse::<isize>() {
+            Ok(number) => number.to_string() == string,
+            Err(_) => true,
+        };
+
+    match is_plain || string.contains('"') {
+        true => string.to_owned(),
+        false => format!("\"{string}\""),
+    }
+}
+
+// a string used as a command name or a value. keywords and numbers are quoted
+// as well, they would not be read back as a string otherwise.
+pub fn quote_value(string: &str) -> String {
+    if KEYWORDS.contains(&string) || string.parse::<isize>().is_ok() {
+        return format!("\"{string}\"");
+    }
+
+    quote_argument(string)
+}
+
+impl Display for Block {
+    // top level statements, one per line.
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        for (i, statement) in self.statements.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+
+            write_statement(f, statement, 0)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write_statement(f, self, 0)
+    }
+}
+
+impl Display for Loop {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write_loop(f, self, 0)
+    }
+}
+
+impl Display for If {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write_if(f, self, 0)
+    }
+}
+
+impl Display for Def {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write_def(f, self, 0)
+    }
+}
+
+impl Display for Return {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match &self.expr {
+            Some(expr) => write!(f, "return {}", Value(expr)),
+            None => write!(f, "return"),
+        }
+    }
+}
+
+impl Display for Assign {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{} = {}", Value(&self.identify), Value(&self.expr))
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Expression::Command(node) => write!(f, "{node}"),
+            Expression::Redirect(node) => write!(f, "{node}"),
+            Expression::Pipe(node) => write!(f, "{node}"),
+            Expression::Logical(node) => write!(f, "{node}"),
+            Expression::Time(node) => write!(f, "{node}"),
+            Expression::Comparison(node) => write!(f, "{node}"),
+            Expression::Background(true) => write!(f, "&"),
+            Expression::Background(false) => Ok(()),
+            _ => write!(f, "{}", Value(self)),
+        }
+    }
+}
+
+impl Display for Command {
+    // true and false read back as commands in this position, they need no quotes.
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self.prefix.as_ref() {
+            Expression::String(name) if name == "true" || name == "false" => write!(f, "{name}")?,
+            prefix => write!(f, "{}", Value(prefix))?,
+        }
+
+        if self.suffix.is_empty() == false {
+            write!(f, " {}", self.suffix)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for CommandSuffix {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        for (i, expr) in self.exprs.iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+
+            match expr {
+                Expression::String(string) => write!(f, "{}", quote_argument(string))?,
+                _ => write!(f, "{expr}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Redirect {
+    // the fd is left out when it is the default one for the operator.
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let (operator, fd) = match self.kind {
+            RedirectKind::Write => (">", 1),
+            RedirectKind::Read => ("<", 0),
+        };
+
+        match self.left.as_ref() {
+            Expression::FD(n) if *n == fd => write!(f, "{operator} {}", Value(&self.right)),
+            left => write!(f, "{} {operator} {}", Value(left), Value(&self.right)),
+        }
+    }
+}
+
+impl Display for Pipe {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match (&self.left, &self.right) {
+            (Some(left), Some(right)) => write!(f, "{left} | {right}"),
+            (Some(expr), None) | (None, Some(expr)) => write!(f, "{expr}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl Display for Logical {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{} {} {}", self.left, self.kind, self.right)
+    }
+}
+
+impl Display for Time {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "time {}", self.pipeline)
+    }
+}
+
+impl Display for LogicalKind {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            LogicalKind::And => write!(f, "&&"),
+            LogicalKind::Or => write!(f, "||"),
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(
+            f,
+            "{} {} {}",
+            Value(&self.left),
+            self.kind,
+            Value(&self.right)
+        )
+    }
+}
+
+impl Display for ComparisonKind {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            ComparisonKind::Equal => write!(f, "=="),
+            ComparisonKind::NotEqual => write!(f, "!="),
+            ComparisonKind::Gt => write!(f, ">"),
+            ComparisonKind::Lt => write!(f, "<"),
+        }
+    }
+}
+
+// an expression in value position: command name, assignment, comparison,
+// return value or redirect target.
+struct Value<'a>(&'a Expression);
+
+impl Display for Value<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self.0 {
+            Expression::String(string) => write!(f, "{}", quote_value(string)),
+            Expression::Variable(name) => write!(f, "${name}"),
+            Expression::Number(number) => write!(f, "{number}"),
+            Expression::Boolean(boolean) => write!(f, "{boolean}"),
+            Expression::FD(fd) => write!(f, "&{fd}"),
+            expr => write!(f, "{expr}"),
+        }
+    }
+}
+
+fn write_indent(f: &mut Formatter, indent: usize) -> Result {
+    write!(f, "{}", INDENT.repeat(indent))
+}
+
+fn write_statement(f: &mut Formatter, statement: &Statement, indent: usize) -> Result {
+    match statement {
+        Statement::Loop(node) => write_loop(f, node, indent),
+        Statement::If(node) => write_if(f, node, indent),
+        Statement::Def(node) => write_def(f, node, indent),
+        Statement::Block(node) => write_block(f, node, indent),
+        Statement::Assign(node) => write!(f, "{node}"),
+        Statement::Return(node) => write!(f, "{node}"),
+        Statement::Expression(expr) => write!(f, "{expr}"),
+    }
+}
+
+// { ... } with the statements one level deeper than indent.
+fn write_block(f: &mut Formatter, block: &Block, indent: usize) -> Result {
+    if block.is_empty() {
+        return write!(f, "{{}}");
+    }
+
+    writeln!(f, "{{")?;
+
+    for statement in block {
+        write_indent(f, indent + 1)?;
+        write_statement(f, statement, indent + 1)?;
+        writeln!(f)?;
+    }
+
+    write_indent(f, indent)?;
+
+    write!(f, "}}")
+}
+
+fn write_loop(f: &mut Formatter, node: &Loop, indent: usize) -> Result {
+    write!(f, "loop {} ", node.comparison)?;
+
+    write_block(f, &node.block, indent)
+}
+
+fn write_if(f: &mut Formatter, node: &If, indent: usize) -> Result {
+    write!(f, "if {} ", node.comparison)?;
+
+    write_block(f, &node.block, indent)?;
+
+    for child in &node.children {
+        write!(f, " elif {} ", child.comparison)?;
+        write_block(f, &child.block, indent)?;
+    }
+
+    if let Some(block) = &node.else_block {
+        write!(f, " else ")?;
+        write_block(f, block, indent)?;
+    }
+
+    Ok(())
+}
+
+fn write_def(f: &mut Formatter, node: &Def, indent: usize) -> Result {
+    let params = node
+        .params
+        .iter()
+        .map(|param| format!("${param}"))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    write!(f, "def {}({params}) ", quote_value(&node.name))?;
+
+    write_block(f, &node.block, indent)
+}
@@ -0,0 +1,369 @@
+use crate::*;
+
+// every visit method defaults to the matching walk function, which visits the
+// children of the node. override a method to handle a node and call the walk
+// function from it to keep descending.
+pub trait Visitor {
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement)
+    }
+
+    fn visit_loop(&mut self, node: &Loop) {
+        walk_loop(self, node)
+    }
+
+    fn visit_if(&mut self, node: &If) {
+        walk_if(self, node)
+    }
+
+    fn visit_assign(&mut self, node: &Assign) {
+        walk_assign(self, node)
+    }
+
+    fn visit_block(&mut self, node: &Block) {
+        walk_block(self, node)
+    }
+
+    fn visit_def(&mut self, node: &Def) {
+        walk_def(self, node)
+    }
+
+    fn visit_return(&mut self, node: &Return) {
+        walk_return(self, node)
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        walk_expression(self, expr)
+    }
+
+    fn visit_command(&mut self, node: &Command) {
+        walk_command(self, node)
+    }
+
+    fn visit_command_suffix(&mut self, node: &CommandSuffix) {
+        walk_command_suffix(self, node)
+    }
+
+    fn visit_redirect(&mut self, node: &Redirect) {
+        walk_redirect(self, node)
+    }
+
+    fn visit_pipe(&mut self, node: &Pipe) {
+        walk_pipe(self, node)
+    }
+
+    fn visit_logical(&mut self, node: &Logical) {
+        walk_logical(self, node)
+    }
+
+    fn visit_time(&mut self, node: &Time) {
+        walk_time(self, node)
+    }
+
+    fn visit_comparison(&mut self, node: &Comparison) {
+        walk_comparison(self, node)
+    }
+
+    fn visit_string(&mut self, _string: &str) {}
+
+    fn visit_variable(&mut self, _name: &str) {}
+
+    fn visit_number(&mut self, _number: isize) {}
+
+    fn visit_boolean(&mut self, _boolean: bool) {}
+
+    fn visit_fd(&mut self, _fd: u32) {}
+
+    fn visit_background(&mut self, _background: bool) {}
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Loop(node) => visitor.visit_loop(node),
+        Statement::If(node) => visitor.visit_if(node),
+        Statement::Assign(node) => visitor.visit_assign(node),
+        Statement::Block(node) => visitor.visit_block(node),
+        Statement::Def(node) => visitor.visit_def(node),
+        Statement::Return(node) => visitor.visit_return(node),
+        Statement::Expression(expr) => visitor.visit_expression(expr),
+    }
+}
+
+pub fn walk_loop<V: Visitor + ?Sized>(visitor: &mut V, node: &Loop) {
+    visitor.visit_comparison(node.comparison());
+    visitor.visit_block(node.block());
+}
+
+pub fn walk_if<V: Visitor + ?Sized>(visitor: &mut V, node: &If) {
+    visitor.visit_comparison(node.comparison());
+    visitor.visit_block(node.block());
+
+    for child in node.children() {
+        visitor.visit_if(child);
+    }
+
+    if let Some(block) = node.else_block() {
+        visitor.visit_block(block);
+    }
+}
+
+pub fn walk_assign<V: Visitor + ?Sized>(visitor: &mut V, node: &Assign) {
+    visitor.visit_expression(node.identify());
+    visitor.visit_expression(node.expr());
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, node: &Block) {
+    for statement in node {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_def<V: Visitor + ?Sized>(visitor: &mut V, node: &Def) {
+    visitor.visit_block(node.block());
+}
+
+pub fn walk_return<V: Visitor + ?Sized>(visitor: &mut V, node: &Return) {
+    if let Some(expr) = node.expr() {
+        visitor.visit_expression(expr);
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expression) {
+    match expr {
+        Expression::String(string) => visitor.visit_string(string),
+        Expression::Variable(name) => visitor.visit_variable(name),
+        Expression::Number(number) => visitor.visit_number(*number),
+        Expression::Background(background) => visitor.visit_background(*background),
+        Expression::Boolean(boolean) => visitor.visit_boolean(*boolean),
+        Expression::FD(fd) => visitor.visit_fd(*fd),
+        Expression::Command(node) => visitor.visit_command(node),
+        Expression::Redirect(node) => visitor.visit_redirect(node),
+        Expression::Pipe(node) => visitor.visit_pipe(node),
+        Expression::Logical(node) => visitor.visit_logical(node),
+        Expression::Time(node) => visitor.visit_time(node),
+        Expression::Comparison(node) => visitor.visit_comparison(node),
+    }
+}
+
+pub fn walk_command<V: Visitor + ?Sized>(visitor: &mut V, node: &Command) {
+    visitor.visit_expression(node.prefix());
+    visitor.visit_command_suffix(node.suffix());
+}
+
+pub fn walk_command_suffix<V: Visitor + ?Sized>(visitor: &mut V, node: &CommandSuffix) {
+    for expr in node {
+        visitor.visit_expression(expr);
+    }
+}
+
+pub fn walk_redirect<V: Visitor + ?Sized>(visitor: &mut V, node: &Redirect) {
+    visitor.visit_expression(node.left());
+    visitor.visit_expression(node.right());
+}
+
+pub fn walk_pipe<V: Visitor + ?Sized>(visitor: &mut V, node: &Pipe) {
+    if let Some(left) = node.left() {
+        visitor.visit_expression(left);
+    }
+
+    if let Some(right) = node.right() {
+        visitor.visit_expression(right);
+    }
+}
+
+pub fn walk_logical<V: Visitor + ?Sized>(visitor: &mut V, node: &Logical) {
+    visitor.visit_expression(node.left());
+    visitor.visit_expression(node.right());
+}
+
+pub fn walk_time<V: Visitor + ?Sized>(visitor: &mut V, node: &Time) {
+    visitor.visit_expression(node.pipeline());
+}
+
+pub fn walk_comparison<V: Visitor + ?Sized>(visitor: &mut V, node: &Comparison) {
+    visitor.visit_expression(node.left());
+    visitor.visit_expression(node.right());
+}
+
+// same as Visitor, but the nodes can be rewritten in place.
+pub trait VisitorMut {
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement)
+    }
+
+    fn visit_loop_mut(&mut self, node: &mut Loop) {
+        walk_loop_mut(self, node)
+    }
+
+    fn visit_if_mut(&mut self, node: &mut If) {
+        walk_if_mut(self, node)
+    }
+
+    fn visit_assign_mut(&mut self, node: &mut Assign) {
+        walk_assign_mut(self, node)
+    }
+
+    fn visit_block_mut(&mut self, node: &mut Block) {
+        walk_block_mut(self, node)
+    }
+
+    fn visit_def_mut(&mut self, node: &mut Def) {
+        walk_def_mut(self, node)
+    }
+
+    fn visit_return_mut(&mut self, node: &mut Return) {
+        walk_return_mut(self, node)
+    }
+
+    fn visit_expression_mut(&mut self, expr: &mut Expression) {
+        walk_expression_mut(self, expr)
+    }
+
+    fn visit_command_mut(&mut self, node: &mut Command) {
+        walk_command_mut(self, node)
+    }
+
+    fn visit_command_suffix_mut(&mut self, node: &mut CommandSuffix) {
+        walk_command_suffix_mut(self, node)
+    }
+
+    fn visit_redirect_mut(&mut self, node: &mut Redirect) {
+        walk_redirect_mut(self, node)
+    }
+
+    fn visit_pipe_mut(&mut self, node: &mut Pipe) {
+        walk_pipe_mut(self, node)
+    }
+
+    fn visit_logical_mut(&mut self, node: &mut Logical) {
+        walk_logical_mut(self, node)
+    }
+
+    fn visit_time_mut(&mut self, node: &mut Time) {
+        walk_time_mut(self, node)
+    }
+
+    fn visit_comparison_mut(&mut self, node: &mut Comparison) {
+        walk_comparison_mut(self, node)
+    }
+
+    fn visit_string_mut(&mut self, _string: &mut String) {}
+
+    fn visit_variable_mut(&mut self, _name: &mut String) {}
+
+    fn visit_number_mut(&mut self, _number: &mut isize) {}
+
+    fn visit_boolean_mut(&mut self, _boolean: &mut bool) {}
+
+    fn visit_fd_mut(&mut self, _fd: &mut u32) {}
+
+    fn visit_background_mut(&mut self, _background: &mut bool) {}
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Loop(node) => visitor.visit_loop_mut(node),
+        Statement::If(node) => visitor.visit_if_mut(node),
+        Statement::Assign(node) => visitor.visit_assign_mut(node),
+        Statement::Block(node) => visitor.visit_block_mut(node),
+        Statement::Def(node) => visitor.visit_def_mut(node),
+        Statement::Return(node) => visitor.visit_return_mut(node),
+        Statement::Expression(expr) => visitor.visit_expression_mut(expr),
+    }
+}
+
+pub fn walk_loop_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Loop) {
+    visitor.visit_comparison_mut(node.comparison_mut());
+    visitor.visit_block_mut(node.block_mut());
+}
+
+pub fn walk_if_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut If) {
+    visitor.visit_comparison_mut(node.comparison_mut());
+    visitor.visit_block_mut(node.block_mut());
+
+    for child in node.children_mut() {
+        visitor.visit_if_mut(child);
+    }
+
+    if let Some(block) = node.else_block_mut() {
+        visitor.visit_block_mut(block);
+    }
+}
+
+pub fn walk_assign_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Assign) {
+    visitor.visit_expression_mut(node.identify_mut());
+    visitor.visit_expression_mut(node.expr_mut());
+}
+
+pub fn walk_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Block) {
+    for statement in node.statements_mut() {
+        visitor.visit_statement_mut(statement);
+    }
+}
+
+pub fn walk_def_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Def) {
+    visitor.visit_block_mut(node.block_mut());
+}
+
+pub fn walk_return_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Return) {
+    if let Some(expr) = node.expr_mut() {
+        visitor.visit_expression_mut(expr);
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expression) {
+    match expr {
+        Expression::String(string) => visitor.visit_string_mut(string),
+        Expression::Variable(name) => visitor.visit_variable_mut(name),
+        Expression::Number(number) => visitor.visit_number_mut(number),
+        Expression::Background(background) => visitor.visit_background_mut(background),
+        Expression::Boolean(boolean) => visitor.visit_boolean_mut(boolean),
+        Expression::FD(fd) => visitor.visit_fd_mut(fd),
+        Expression::Command(node) => visitor.visit_command_mut(node),
+        Expression::Redirect(node) => visitor.visit_redirect_mut(node),
+        Expression::Pipe(node) => visitor.visit_pipe_mut(node),
+        Expression::Logical(node) => visitor.visit_logical_mut(node),
+        Expression::Time(node) => visitor.visit_time_mut(node),
+        Expression::Comparison(node) => visitor.visit_comparison_mut(node),
+    }
+}
+
+pub fn walk_command_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Command) {
+    visitor.visit_expression_mut(node.prefix_mut());
+    visitor.visit_command_suffix_mut(node.suffix_mut());
+}
+
+pub fn walk_command_suffix_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut CommandSuffix) {
+    for expr in node.exprs_mut() {
+        visitor.visit_expression_mut(expr);
+    }
+}
+
+pub fn walk_redirect_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Redirect) {
+    visitor.visit_expression_mut(node.left_mut());
+    visitor.visit_expression_mut(node.right_mut());
+}
+
+pub fn walk_pipe_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Pipe) {
+    if let Some(left) = node.left_mut() {
+        visitor.visit_expression_mut(left);
+    }
+
+    if let Some(right) = node.right_mut() {
+        visitor.visit_expression_mut(right);
+    }
+}
+
+pub fn walk_logical_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Logical) {
+    visitor.visit_expression_mut(node.left_mut());
+    visitor.visit_expression_mut(node.right_mut());
+}
+
+pub fn walk_time_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Time) {
+    visitor.visit_expression_mut(node.pipeline_mut());
+}
+
+pub fn walk_comparison_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Comparison) {
+    visitor.visit_expression_mut(node.left_mut());
+    visitor.visit_expression_mut(node.right_mut());
+}
@@ -0,0 +1,23 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use std::process;
+
+pub struct Abort;
+
+impl Builtin for Abort {
+    fn name(&self) -> &'static str {
+        "abort"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "abort"
+    }
+
+    fn description(&self) -> &'static str {
+        "Abort the shell process."
+    }
+
+    fn run(&self, _ctx: &mut Context) -> i32 {
+        process::abort()
+    }
+}
@@ -0,0 +1,142 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::escape;
+use crate::options;
+use std::io::Write;
+
+pub struct Alias;
+
+pub struct Unalias;
+
+impl Builtin for Alias {
+    fn name(&self) -> &'static str {
+        "alias"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "alias [-p] [name[=text] ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Define each name=text as an alias: a command starting with name runs \
+         text instead, followed by the rest of the command. The first word of \
+         text may be an alias too, and when text ends in a blank so may the word \
+         after it. A name alone prints its alias, no names or -p print every \
+         alias as alias commands."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-p", "print every alias as an alias command")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, args) = match options::short_flags(ctx.args(), "p") {
+            Ok((flags, args)) => (flags, args.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        let mut output = String::new();
+
+        if args.is_empty() || flags.contains(&'p') {
+            for (name, text) in ctx.state().aliases() {
+                output.push_str(&format!("alias {name}={}\n", escape::quote(text)));
+            }
+        }
+
+        let mut status = 0;
+
+        for arg in &args {
+            let (name, text) = match arg.split_once('=') {
+                Some((name, text)) => (name, Some(text)),
+                None => (arg.as_str(), None),
+            };
+
+            if is_name(name) == false {
+                status = ctx.error(&format!("`{name}': invalid alias name"));
+                continue;
+            }
+
+            match (text, ctx.state().alias(name)) {
+                (Some(text), _) => ctx.state_mut().set_alias(name, text.to_owned()),
+                (None, Some(text)) => {
+                    output.push_str(&format!("alias {name}={}\n", escape::quote(text)))
+                }
+                (None, None) => status = ctx.error(&format!("{name}: not found")),
+            }
+        }
+
+        match ctx.stdout.write_all(output.as_bytes()) {
+            Ok(_) => status,
+            Err(err) => ctx.error(&format!("write error: {err}")),
+        }
+    }
+}
+
+impl Builtin for Unalias {
+    fn name(&self) -> &'static str {
+        "unalias"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "unalias [-a] name ..."
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove the alias of each name, -a removes every alias."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-a", "remove every alias")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, names) = match options::short_flags(ctx.args(), "a") {
+            Ok((flags, names)) => (flags, names.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        if flags.contains(&'a') {
+            ctx.state_mut().clear_aliases();
+            return 0;
+        }
+
+        if names.is_empty() {
+            return ctx.error("usage: unalias [-a] name ...");
+        }
+
+        let mut status = 0;
+
+        for name in &names {
+            if ctx.state_mut().unalias(name) == false {
+                status = ctx.error(&format!("{name}: not found"));
+            }
+        }
+
+        status
+    }
+}
+
+// a word the lexer reads as one plain string.
+fn is_name(name: &str) -> bool {
+    name.is_empty() == false
+        && name.contains(|ch: char| {
+            ch.is_whitespace()
+                || matches!(
+                    ch,
+                    '/' | '$'
+                        | '&'
+                        | '"'
+                        | '\\'
+                        | '#'
+                        | ';'
+                        | ','
+                        | '|'
+                        | '<'
+                        | '>'
+                        | '('
+                        | ')'
+                        | '{'
+                        | '}'
+                )
+        }) == false
+}
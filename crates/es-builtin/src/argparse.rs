@@ -0,0 +1,470 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::state::is_name;
+use std::io::Write;
+
+pub struct Argparse;
+
+// an option as the spec declares it: `v/verbose`, `o/output=FILE:help` ...
+struct Spec {
+    short: Option<char>,
+    long: Option<String>,
+    // what the value is called in the help, None for a flag.
+    value: Option<String>,
+    help: String,
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Option<Self> {
+        let (spec, help) = spec.split_once(':').unwrap_or((spec, ""));
+
+        let (spec, value) = match spec.split_once('=') {
+            Some((spec, "")) => (spec, Some("VALUE".to_owned())),
+            Some((spec, value)) => (spec, Some(value.to_owned())),
+            None => (spec, None),
+        };
+
+        let (short, long) = match spec.split_once('/') {
+            Some((short, long)) => (short, long),
+            None if spec.chars().count() == 1 => (spec, ""),
+            None => ("", spec),
+        };
+
+        let mut chars = short.chars();
+
+        let short = match (chars.next(), chars.next()) {
+            (None, _) => None,
+            (Some(ch), None) if ch.is_ascii_alphanumeric() => Some(ch),
+            _ => return None,
+        };
+
+        let long = match long {
+            "" => None,
+            long if long
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-') =>
+            {
+                Some(long.to_owned())
+            }
+            _ => return None,
+        };
+
+        if short.is_none() && long.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            short: short,
+            long: long,
+            value: value,
+            help: help.to_owned(),
+        })
+    }
+
+    // opt_ and the long name with `-` as `_`, or the short one.
+    fn variable(&self) -> String {
+        match (&self.long, self.short) {
+            (Some(long), _) => format!("opt_{}", long.replace('-', "_")),
+            (None, Some(short)) => format!("opt_{short}"),
+            (None, None) => unreachable!(),
+        }
+    }
+
+    // `-o, --output=FILE` as in the help.
+    fn usage(&self) -> String {
+        let value = self.value.as_deref().unwrap_or_default();
+
+        match (self.short, &self.long) {
+            (Some(short), Some(long)) if value.is_empty() => format!("-{short}, --{long}"),
+            (Some(short), Some(long)) => format!("-{short}, --{long}={value}"),
+            (Some(short), None) if value.is_empty() => format!("-{short}"),
+            (Some(short), None) => format!("-{short} {value}"),
+            (None, Some(long)) if value.is_empty() => format!("    --{long}"),
+            (None, Some(long)) => format!("    --{long}={value}"),
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+impl Builtin for Argparse {
+    fn name(&self) -> &'static str {
+        "argparse"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "argparse [-n name] [-d description] [-u usage] spec ... [-- arg ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Parse the options of the positional parameters, or of the args after \
+         `--`, the way a spec declares them, and leave the operands as the \
+         positional parameters. A spec is `[s/]long[=VALUE][:help]`: a short \
+         and a long name, `=` for an option that takes a value, and a line of \
+         help. Each option sets opt_long, `-` in the name becoming `_`, or \
+         opt_s without a long name: to true or false for a flag, to the value \
+         or an empty string otherwise. Options take `-s value`, `-svalue`, \
+         `--long value` and `--long=value`, flags can be grouped as in `-abc` \
+         and `--` ends the options. -h and --help print a help built from the \
+         specs, with name, the description and usage for the operands, then \
+         exit the shell with 0. Returns 2 for an unknown option or a missing \
+         value."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-n name", "the name the help shows, $0 by default"),
+            ("-d description", "a description for the help"),
+            ("-u usage", "how the help describes the operands"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let mut name = None;
+
+        let mut about = None;
+
+        let mut operands = None;
+
+        let mut args = ctx.args();
+
+        while let [flag, value, rest @ ..] = args {
+            match flag.as_str() {
+                "-n" => name = Some(value.clone()),
+                "-d" => about = Some(value.clone()),
+                "-u" => operands = Some(value.clone()),
+                _ => break,
+            }
+
+            args = rest;
+        }
+
+        let (specs, args) = match args.iter().position(|arg| arg == "--") {
+            Some(i) => (args[..i].to_vec(), args[i + 1..].to_vec()),
+            None => (args.to_vec(), ctx.state().args().to_vec()),
+        };
+
+        let mut options = Vec::new();
+
+        for spec in &specs {
+            match Spec::parse(spec) {
+                Some(option) => options.push(option),
+                None => return ctx.error(&format!("{spec}: invalid option spec")),
+            }
+        }
+
+        // -h and --help unless a spec has them.
+        let help = Spec {
+            short: Some('h').filter(|h| options.iter().all(|option| option.short != Some(*h))),
+            long: Some("help".to_owned()).filter(|help| {
+                options
+                    .iter()
+                    .all(|option| option.long.as_ref() != Some(help))
+            }),
+            value: None,
+            help: "show this help and exit".to_owned(),
+        };
+
+        let name = name.unwrap_or_else(|| {
+            let name = ctx.state().name();
+
+            name.rsplit('/').next().unwrap_or(name).to_owned()
+        });
+
+        let mut values: Vec<Option<String>> = vec![None; options.len()];
+
+        let mut rest = Vec::new();
+
+        let mut is_help = false;
+
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                rest.extend(args.by_ref());
+                break;
+            }
+
+            if let Some(long) = arg.strip_prefix("--") {
+                let (long, value) = match long.split_once('=') {
+                    Some((long, value)) => (long, Some(value.to_owned())),
+                    None => (long, None),
+                };
+
+                if help.long.as_deref() == Some(long) {
+                    is_help = true;
+                    continue;
+                }
+
+                let i = match options
+                    .iter()
+                    .position(|option| option.long.as_deref() == Some(long))
+                {
+                    Some(i) => i,
+                    None => return usage_error(ctx, &name, &format!("unknown option --{long}")),
+                };
+
+                values[i] = match (&options[i].value, value) {
+                    (None, None) => Some("true".to_owned()),
+                    (None, Some(_)) => {
+                        return usage_error(ctx, &name, &format!("--{long} takes no value"))
+                    }
+                    (Some(_), Some(value)) => Some(value),
+                    (Some(_), None) => match args.next() {
+                        Some(value) => Some(value),
+                        None => return usage_error(ctx, &name, &format!("--{long} needs a value")),
+                    },
+                };
+
+                continue;
+            }
+
+            if arg.len() < 2 || arg.starts_with('-') == false {
+                rest.push(arg);
+                continue;
+            }
+
+            let flags: Vec<char> = arg.chars().skip(1).collect();
+
+            for (j, flag) in flags.iter().enumerate() {
+                if help.short == Some(*flag) {
+                    is_help = true;
+                    continue;
+                }
+
+                let i = match options
+                    .iter()
+                    .position(|option| option.short == Some(*flag))
+                {
+                    Some(i) => i,
+                    None => return usage_error(ctx, &name, &format!("unknown option -{flag}")),
+                };
+
+                if options[i].value.is_none() {
+                    values[i] = Some("true".to_owned());
+                    continue;
+                }
+
+                // the rest of the argument is the value, or else the next one.
+                values[i] = match flags[j + 1..].iter().collect::<String>() {
+                    value if value.is_empty() == false => Some(value),
+                    _ => match args.next() {
+                        Some(value) => Some(value),
+                        None => return usage_error(ctx, &name, &format!("-{flag} needs a value")),
+                    },
+                };
+
+                break;
+            }
+        }
+
+        if is_help {
+            options.push(help);
+
+            let _ = write!(
+                ctx.stdout,
+                "{}",
+                help_text(&name, about.as_deref(), operands.as_deref(), &options)
+            );
+
+            ctx.state_mut().exit(0);
+
+            return 0;
+        }
+
+        for (option, value) in options.iter().zip(values) {
+            let value = match (value, &option.value) {
+                (Some(value), _) => value,
+                (None, None) => "false".to_owned(),
+                (None, Some(_)) => String::new(),
+            };
+
+            let variable = option.variable();
+
+            if is_name(&variable) == false {
+                return ctx.error(&format!("{variable}: not a valid identifier"));
+            }
+
+            if let Err(message) = ctx.state_mut().assign(&variable, value) {
+                return ctx.error(&message);
+            }
+        }
+
+        ctx.state_mut().set_args(rest);
+
+        0
+    }
+}
+
+// prints the error and where to find the help, returns 2.
+fn usage_error(ctx: &mut Context, name: &str, message: &str) -> i32 {
+    let _ = writeln!(
+        ctx.stderr,
+        "{name}: {message}\ntry `{name} --help` for more information"
+    );
+
+    2
+}
+
+fn help_text(name: &str, about: Option<&str>, operands: Option<&str>, options: &[Spec]) -> String {
+    let mut text = match operands {
+        Some(operands) => format!("usage: {name} [options] {operands}\n"),
+        None => format!("usage: {name} [options]\n"),
+    };
+
+    if let Some(about) = about {
+        text.push_str(&format!("\n{about}\n"));
+    }
+
+    text.push_str("\noptions:\n");
+
+    let usages: Vec<String> = options.iter().map(|option| option.usage()).collect();
+
+    let width = usages.iter().map(|usage| usage.len()).max().unwrap_or(0);
+
+    for (usage, option) in usages.iter().zip(options) {
+        let line = format!("  {usage:width$}  {}", option.help);
+
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::Shell;
+    use crate::testing::TestShell;
+
+    fn variable(shell: &TestShell, name: &str) -> Option<String> {
+        shell.state().variable(name)
+    }
+
+    #[test]
+    fn specs_name_options_and_their_values() {
+        let spec = Spec::parse("o/output=FILE:where to write").unwrap();
+
+        assert_eq!(
+            (spec.short, spec.long.as_deref(), spec.value.as_deref()),
+            (Some('o'), Some("output"), Some("FILE"))
+        );
+        assert_eq!(spec.help, "where to write");
+        assert_eq!(spec.variable(), "opt_output");
+        assert_eq!(spec.usage(), "-o, --output=FILE");
+
+        let spec = Spec::parse("dry-run").unwrap();
+
+        assert_eq!((spec.short, spec.value.as_deref()), (None, None));
+        assert_eq!(spec.variable(), "opt_dry_run");
+        assert_eq!(spec.usage(), "    --dry-run");
+
+        assert_eq!(Spec::parse("n=").unwrap().usage(), "-n VALUE");
+        assert!(Spec::parse("ab/long").is_none());
+        assert!(Spec::parse("x/bad_name").is_none());
+        assert!(Spec::parse("=FILE").is_none());
+    }
+
+    #[test]
+    fn options_set_variables_and_operands_stay() {
+        let mut shell = TestShell::new();
+
+        let argv = [
+            "argparse",
+            "v/verbose",
+            "o/output=",
+            "n",
+            "--",
+            "-vo",
+            "out",
+            "a",
+            "-n",
+            "--",
+            "-v",
+        ];
+
+        assert_eq!(shell.run(&argv), ("".into(), "".into(), 0));
+        assert_eq!(variable(&shell, "opt_verbose").as_deref(), Some("true"));
+        assert_eq!(variable(&shell, "opt_output").as_deref(), Some("out"));
+        assert_eq!(variable(&shell, "opt_n").as_deref(), Some("true"));
+        assert_eq!(shell.state().args(), ["a", "-v"]);
+
+        let argv = [
+            "argparse",
+            "v/verbose",
+            "o/output=",
+            "--",
+            "--output=x",
+            "-",
+        ];
+
+        assert_eq!(shell.run(&argv).2, 0);
+        assert_eq!(variable(&shell, "opt_verbose").as_deref(), Some("false"));
+        assert_eq!(variable(&shell, "opt_output").as_deref(), Some("x"));
+        assert_eq!(shell.state().args(), ["-"]);
+    }
+
+    #[test]
+    fn bad_options_are_usage_errors() {
+        let mut shell = TestShell::new();
+
+        let error = |message: &str| {
+            (
+                "".to_owned(),
+                format!("t: {message}\ntry `t --help` for more information\n"),
+                2,
+            )
+        };
+
+        assert_eq!(
+            shell.run(&["argparse", "-n", "t", "v", "--", "-x"]),
+            error("unknown option -x")
+        );
+        assert_eq!(
+            shell.run(&["argparse", "-n", "t", "v", "--", "--v=1"]),
+            error("unknown option --v")
+        );
+        assert_eq!(
+            shell.run(&["argparse", "-n", "t", "f/flag", "--", "--flag=1"]),
+            error("--flag takes no value")
+        );
+        assert_eq!(
+            shell.run(&["argparse", "-n", "t", "o=", "--", "-o"]),
+            error("-o needs a value")
+        );
+        assert_eq!(
+            shell.run(&["argparse", "a/b/c"]),
+            (
+                "".into(),
+                "eash: argparse: a/b/c: invalid option spec\n".into(),
+                1
+            )
+        );
+    }
+
+    #[test]
+    fn help_lists_the_specs_and_exits() {
+        let mut shell = TestShell::new();
+
+        let argv = [
+            "argparse",
+            "-n",
+            "t",
+            "-u",
+            "file",
+            "v/verbose:talk more",
+            "--",
+            "-h",
+        ];
+
+        let (stdout, _, status) = shell.run(&argv);
+
+        assert_eq!(status, 0);
+        assert_eq!(
+            stdout,
+            "usage: t [options] file\n\noptions:\n  -v, --verbose  talk more\n  \
+             -h, --help     show this help and exit\n"
+        );
+        assert_eq!(shell.state_mut().take_exit(), Some(0));
+    }
+}
@@ -0,0 +1,50 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+
+pub struct True;
+
+pub struct False;
+
+impl Builtin for True {
+    fn name(&self) -> &'static str {
+        "true"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "true"
+    }
+
+    fn description(&self) -> &'static str {
+        "Do nothing, successfully."
+    }
+
+    fn has_help(&self) -> bool {
+        false
+    }
+
+    fn run(&self, _ctx: &mut Context) -> i32 {
+        0
+    }
+}
+
+impl Builtin for False {
+    fn name(&self) -> &'static str {
+        "false"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "false"
+    }
+
+    fn description(&self) -> &'static str {
+        "Do nothing, unsuccessfully."
+    }
+
+    fn has_help(&self) -> bool {
+        false
+    }
+
+    fn run(&self, _ctx: &mut Context) -> i32 {
+        1
+    }
+}
@@ -0,0 +1,81 @@
+use crate::io::Fd;
+use crate::shell::Shell;
+use crate::state::State;
+use std::io::Write;
+
+// what a builtin gets to run with: its argv, argv[0] being its name, the fds the
+// engine set up for it after pipes and redirections, and the shell.
+pub struct Context<'a> {
+    pub argv: &'a [String],
+    pub stdin: Fd,
+    pub stdout: Fd,
+    pub stderr: Fd,
+    pub shell: &'a mut dyn Shell,
+}
+
+impl Context<'_> {
+    pub fn state(&self) -> &State {
+        self.shell.state()
+    }
+
+    pub fn state_mut(&mut self) -> &mut State {
+        self.shell.state_mut()
+    }
+
+    pub fn io(&self) -> [Fd; 3] {
+        [self.stdin, self.stdout, self.stderr]
+    }
+
+    pub fn name(&self) -> &str {
+        self.argv
+            .first()
+            .map(|name| name.as_str())
+            .unwrap_or_default()
+    }
+
+    // the arguments after the name.
+    pub fn args(&self) -> &[String] {
+        self.argv.get(1..).unwrap_or_default()
+    }
+
+    // prints `eash: name: message` to stderr and returns 1, the usual status of
+    // a failed builtin.
+    pub fn error(&mut self, message: &str) -> i32 {
+        let name = self.name().to_owned();
+
+        let _ = writeln!(self.stderr, "eash: {name}: {message}");
+
+        1
+    }
+
+    // writes output to stdout and returns 0, or reports the write error.
+    pub fn write(&mut self, output: &str) -> i32 {
+        match self.stdout.write_all(output.as_bytes()) {
+            Ok(_) => 0,
+            Err(err) => self.error(&format!("write error: {err}")),
+        }
+    }
+}
+
+// a command run inside the shell process. run returns the exit status.
+pub trait Builtin {
+    fn name(&self) -> &'static str;
+
+    fn synopsis(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    // the options it takes as written, with a value if they have one, and what
+    // each does. for help, completion and hover.
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    // whether `name --help` describes it instead of running it. false for the
+    // builtins that take every argument as an operand, like echo and test.
+    fn has_help(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32;
+}
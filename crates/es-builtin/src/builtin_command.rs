@@ -0,0 +1,38 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::shell::Lookup;
+
+// the `builtin` builtin.
+pub struct BuiltinCommand;
+
+impl Builtin for BuiltinCommand {
+    fn name(&self) -> &'static str {
+        "builtin"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "builtin name [arg...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run the builtin name with the arguments, even when a function of that \
+         name exists."
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let argv = ctx.args().to_vec();
+
+        let name = match argv.first() {
+            Some(name) => name,
+            None => return 0,
+        };
+
+        if ctx.shell.registry().contains(name) == false {
+            return ctx.error(&format!("{name}: not a shell builtin"));
+        }
+
+        let io = ctx.io();
+
+        ctx.shell.execute(&argv, io, Lookup::BuiltinsOnly)
+    }
+}
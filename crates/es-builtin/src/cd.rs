@@ -0,0 +1,161 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::options;
+use crate::pwd;
+use crate::state::State;
+use std::env;
+use std::io::Write;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+pub struct Cd;
+
+impl Builtin for Cd {
+    fn name(&self) -> &'static str {
+        "cd"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "cd [-L|-P] [dir]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Change the working directory to dir, HOME by default. `cd -` goes back to \
+         OLDPWD. Relative dirs are also looked up in the CDPATH directories. With \
+         -L, the default, `..` goes back over symbolic links, -P resolves them."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-L", "go back over symbolic links on `..`, the default"),
+            ("-P", "resolve symbolic links"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, args) = match options::short_flags(ctx.args(), "LP") {
+            Ok((flags, args)) => (flags, args.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        let is_physical = flags.last() == Some(&'P');
+
+        let (dir, is_printed) = match args.as_slice() {
+            [] => match ctx.state().variable("HOME") {
+                Some(home) => (home, false),
+                None => return ctx.error("HOME not set"),
+            },
+            [dir] if dir == "-" => match ctx.state().variable("OLDPWD") {
+                Some(oldpwd) => (oldpwd, true),
+                None => return ctx.error("OLDPWD not set"),
+            },
+            [dir] => (dir.to_owned(), false),
+            _ => return ctx.error("too many arguments"),
+        };
+
+        let (dir, is_found_in_cdpath) = match search_cdpath(ctx, &dir) {
+            Some(found) => (found, true),
+            None => (dir, false),
+        };
+
+        let pwd = match change_dir(ctx, &dir, is_physical) {
+            Ok(pwd) => pwd,
+            Err(err) => return ctx.error(&err),
+        };
+
+        if is_printed || is_found_in_cdpath {
+            let _ = writeln!(ctx.stdout, "{pwd}");
+        }
+
+        0
+    }
+}
+
+// changes the working directory and updates PWD and OLDPWD. returns the new PWD,
+// or the error message.
+pub fn change_dir(ctx: &mut Context, dir: &str, is_physical: bool) -> Result<String, String> {
+    let old = current_dir(ctx.state());
+
+    let logical = match is_physical {
+        true => None,
+        false => Some(normalize(&Path::new(&old).join(dir))),
+    };
+
+    // a logical path whose `..` does not lead anywhere falls back to the
+    // physical one, like other shells.
+    let is_logical = match &logical {
+        Some(path) => env::set_current_dir(path).is_ok(),
+        None => false,
+    };
+
+    if is_logical == false {
+        if let Err(err) = env::set_current_dir(dir) {
+            return Err(format!("{dir}: {err}"));
+        }
+    }
+
+    let pwd = match (logical, is_logical) {
+        (Some(path), true) => path.to_string_lossy().into_owned(),
+        _ => match env::current_dir() {
+            Ok(path) => path.to_string_lossy().into_owned(),
+            Err(err) => return Err(err.to_string()),
+        },
+    };
+
+    env::set_var("OLDPWD", &old);
+
+    env::set_var("PWD", &pwd);
+
+    Ok(pwd)
+}
+
+// PWD when it still names the working directory, the physical path otherwise.
+pub fn current_dir(state: &State) -> String {
+    match state.variable("PWD") {
+        Some(pwd) if pwd::is_current_dir(&pwd) => pwd,
+        _ => env::current_dir()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    }
+}
+
+// a dir not starting with `/`, `.` or `..` is looked up in CDPATH first.
+fn search_cdpath(ctx: &Context, dir: &str) -> Option<String> {
+    let is_relative = dir.starts_with('/') == false
+        && dir != "."
+        && dir != ".."
+        && dir.starts_with("./") == false
+        && dir.starts_with("../") == false;
+
+    if is_relative == false {
+        return None;
+    }
+
+    let cdpath = ctx.state().variable("CDPATH")?;
+
+    cdpath
+        .split(':')
+        .filter(|base| base.is_empty() == false)
+        .map(|base| Path::new(base).join(dir))
+        .find(|path| path.is_dir())
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+// removes `.` and resolves `..` against the preceding component, without
+// looking at the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
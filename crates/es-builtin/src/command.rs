@@ -0,0 +1,79 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::escape;
+use crate::options;
+use crate::path;
+use crate::path::Kind;
+use crate::shell::Lookup;
+use std::io::Write;
+
+pub struct Command;
+
+impl Builtin for Command {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "command [-vV] name [arg...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run name with the arguments, skipping functions of that name. -v prints \
+         what name resolves to and -V describes it."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-v", "print what name resolves to"),
+            ("-V", "describe what name resolves to"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, argv) = match options::short_flags(ctx.args(), "vV") {
+            Ok((flags, argv)) => (flags, argv.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        if flags.is_empty() {
+            if argv.is_empty() {
+                return 0;
+            }
+
+            let io = ctx.io();
+
+            return ctx.shell.execute(&argv, io, Lookup::NoFunctions);
+        }
+
+        let is_verbose = flags.contains(&'V');
+
+        let mut status = 0;
+
+        for name in argv {
+            let line = match path::resolve(&*ctx.shell, &name, false).pop() {
+                None => {
+                    if is_verbose {
+                        ctx.error(&format!("{name}: not found"));
+                    }
+
+                    status = 1;
+                    continue;
+                }
+                Some(Kind::File(file)) if is_verbose => format!("{name} is {}", file.display()),
+                Some(Kind::File(file)) => file.display().to_string(),
+                Some(Kind::Alias(text)) if is_verbose => format!("{name} is aliased to `{text}'"),
+                Some(Kind::Alias(text)) => format!("alias {name}={}", escape::quote(&text)),
+                Some(Kind::Function) if is_verbose => format!("{name} is a function"),
+                Some(Kind::Builtin) if is_verbose => format!("{name} is a shell builtin"),
+                Some(_) => name,
+            };
+
+            if let Err(err) = writeln!(ctx.stdout, "{line}") {
+                return ctx.error(&format!("write error: {err}"));
+            }
+        }
+
+        status
+    }
+}
@@ -0,0 +1,28 @@
+use es_ast::ComparisonKind;
+use std::cmp::Ordering;
+
+// numbers compare as numbers, anything else as strings. this is what the
+// engine does for `left == right` and friends.
+pub fn compare(kind: ComparisonKind, left: &str, right: &str) -> bool {
+    let ordering = match (integer(left), integer(right)) {
+        (Some(left), Some(right)) => left.cmp(&right),
+        _ => left.cmp(right),
+    };
+
+    is_match(kind, ordering)
+}
+
+// whether the ordering of left to right is what kind asks for.
+pub fn is_match(kind: ComparisonKind, ordering: Ordering) -> bool {
+    match kind {
+        ComparisonKind::Equal => ordering == Ordering::Equal,
+        ComparisonKind::NotEqual => ordering != Ordering::Equal,
+        ComparisonKind::Gt => ordering == Ordering::Greater,
+        ComparisonKind::Lt => ordering == Ordering::Less,
+    }
+}
+
+// surrounding whitespace is allowed, like sh does.
+pub fn integer(string: &str) -> Option<isize> {
+    string.trim().parse::<isize>().ok()
+}
@@ -0,0 +1,386 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::cd;
+use crate::state::State;
+use std::io::Write;
+
+pub struct Pushd;
+
+pub struct Popd;
+
+pub struct Dirs;
+
+impl Builtin for Pushd {
+    fn name(&self) -> &'static str {
+        "pushd"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "pushd [-n] [dir | +N | -N]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Save the working directory on the directory stack and change to dir. \
+         Without dir the top two entries are swapped, +N and -N rotate the stack \
+         so that entry N, counted from the top or the bottom, comes first. With \
+         -n dir is only added to the stack."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-n", "only add dir to the stack")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (is_moving, args) = split_no_change(ctx.args());
+
+        let mut entries = entries(ctx);
+
+        let dir = match args.as_slice() {
+            [] => {
+                if entries.len() < 2 {
+                    return ctx.error("no other directory");
+                }
+
+                entries.swap(0, 1);
+
+                entries[0].clone()
+            }
+
+            [index] if is_index(index) => {
+                let n = match position(index, entries.len()) {
+                    Some(n) => n,
+                    None => {
+                        return ctx.error(&format!("{index}: directory stack index out of range"))
+                    }
+                };
+
+                entries.rotate_left(n);
+
+                entries[0].clone()
+            }
+
+            [dir] => {
+                entries.insert(0, dir.to_owned());
+
+                dir.to_owned()
+            }
+
+            _ => return ctx.error("too many arguments"),
+        };
+
+        let is_adding = args.len() == 1 && is_index(&args[0]) == false;
+
+        if is_moving || is_adding == false {
+            match cd::change_dir(ctx, &dir, false) {
+                Ok(pwd) => entries[0] = pwd,
+                Err(err) => return ctx.error(&err),
+            }
+        } else {
+            // -n keeps the working directory on top.
+            entries.swap(0, 1);
+        }
+
+        set_entries(ctx.state_mut(), entries);
+
+        print(ctx, false, false)
+    }
+}
+
+impl Builtin for Popd {
+    fn name(&self) -> &'static str {
+        "popd"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "popd [-n] [+N | -N]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove the top entry of the directory stack and change to the new top. \
+         +N and -N remove entry N, counted from the top or the bottom, instead. \
+         -n only changes the stack."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-n", "only change the stack")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (is_moving, args) = split_no_change(ctx.args());
+
+        let mut entries = entries(ctx);
+
+        if entries.len() < 2 {
+            return ctx.error("directory stack empty");
+        }
+
+        let n = match args.as_slice() {
+            [] => 0,
+            [index] if is_index(index) => match position(index, entries.len()) {
+                Some(n) => n,
+                None => return ctx.error(&format!("{index}: directory stack index out of range")),
+            },
+            [arg] => return ctx.error(&format!("{arg}: invalid argument")),
+            _ => return ctx.error("too many arguments"),
+        };
+
+        // -n never leaves the working directory.
+        let n = match (is_moving, n) {
+            (false, 0) => 1,
+            (_, n) => n,
+        };
+
+        entries.remove(n);
+
+        if n == 0 {
+            match cd::change_dir(ctx, &entries[0], false) {
+                Ok(pwd) => entries[0] = pwd,
+                Err(err) => return ctx.error(&err),
+            }
+        }
+
+        set_entries(ctx.state_mut(), entries);
+
+        print(ctx, false, false)
+    }
+}
+
+impl Builtin for Dirs {
+    fn name(&self) -> &'static str {
+        "dirs"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "dirs [-clpv] [+N | -N]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print the directory stack, the working directory first. -v numbers the \
+         entries one per line, -p prints them one per line, -l does not shorten \
+         HOME to ~ and -c clears the stack. +N and -N print only entry N. Entry N \
+         is also what ~N expands to."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-c", "clear the stack"),
+            ("-l", "do not shorten HOME to ~"),
+            ("-p", "print one entry per line"),
+            ("-v", "print numbered entries one per line"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let mut flags = Vec::new();
+
+        let mut index = None;
+
+        for arg in ctx.args() {
+            if is_index(arg) {
+                index = Some(arg.to_owned());
+                continue;
+            }
+
+            match arg.strip_prefix('-') {
+                Some(chars) if chars.chars().all(|ch| "clpv".contains(ch)) => {
+                    flags.extend(chars.chars())
+                }
+                _ => return ctx.error(&format!("{arg}: invalid argument")),
+            }
+        }
+
+        if flags.contains(&'c') {
+            ctx.state_mut().dirs_mut().clear();
+            return 0;
+        }
+
+        let is_long = flags.contains(&'l');
+
+        if let Some(index) = index {
+            let entries = entries(ctx);
+
+            return match position(&index, entries.len()) {
+                Some(n) => {
+                    let entry = display(ctx.state(), &entries[n], is_long);
+
+                    let _ = writeln!(ctx.stdout, "{entry}");
+
+                    0
+                }
+                None => ctx.error(&format!("{index}: directory stack index out of range")),
+            };
+        }
+
+        match (flags.contains(&'v'), flags.contains(&'p')) {
+            (true, _) => print(ctx, true, is_long),
+            (false, true) => {
+                let lines = entries(ctx)
+                    .iter()
+                    .map(|entry| display(ctx.state(), entry, is_long))
+                    .collect::<Vec<String>>();
+
+                for line in lines {
+                    let _ = writeln!(ctx.stdout, "{line}");
+                }
+
+                0
+            }
+            _ => print(ctx, false, is_long),
+        }
+    }
+}
+
+// the working directory followed by the stack.
+fn entries(ctx: &Context) -> Vec<String> {
+    let state = ctx.state();
+
+    let mut entries = vec![cd::current_dir(state)];
+
+    entries.extend(state.dirs().iter().cloned());
+
+    entries
+}
+
+// entry N of a dirs listing, for ~N: +N or N from the top, -N from the bottom.
+pub fn entry(state: &State, index: &str) -> Option<String> {
+    let mut entries = vec![cd::current_dir(state)];
+
+    entries.extend(state.dirs().iter().cloned());
+
+    position(index, entries.len()).map(|n| entries.swap_remove(n))
+}
+
+fn set_entries(state: &mut State, entries: Vec<String>) {
+    *state.dirs_mut() = entries.into_iter().skip(1).collect();
+}
+
+// -n as the first argument keeps the working directory.
+fn split_no_change(args: &[String]) -> (bool, Vec<String>) {
+    match args.first().map(|arg| arg.as_str()) {
+        Some("-n") => (false, args[1..].to_vec()),
+        _ => (true, args.to_vec()),
+    }
+}
+
+fn is_index(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with(['+', '-']) && arg[1..].chars().all(|ch| ch.is_ascii_digit())
+}
+
+fn position(index: &str, len: usize) -> Option<usize> {
+    let (from_bottom, digits) = match index.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, index.strip_prefix('+').unwrap_or(index)),
+    };
+
+    let n = digits.parse::<usize>().ok()?;
+
+    if n >= len {
+        return None;
+    }
+
+    Some(match from_bottom {
+        true => len - 1 - n,
+        false => n,
+    })
+}
+
+fn display(state: &State, entry: &str, is_long: bool) -> String {
+    let home = match (is_long, state.variable("HOME")) {
+        (false, Some(home)) if home.is_empty() == false && home != "/" => home,
+        _ => return entry.to_owned(),
+    };
+
+    match entry.strip_prefix(&home) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("~{rest}"),
+        _ => entry.to_owned(),
+    }
+}
+
+fn print(ctx: &mut Context, is_numbered: bool, is_long: bool) -> i32 {
+    let entries = entries(ctx)
+        .iter()
+        .map(|entry| display(ctx.state(), entry, is_long))
+        .collect::<Vec<String>>();
+
+    let output = match is_numbered {
+        true => entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| format!("{i:2}  {entry}\n"))
+            .collect::<String>(),
+        false => format!("{}\n", entries.join(" ")),
+    };
+
+    match ctx.stdout.write_all(output.as_bytes()) {
+        Ok(_) => 0,
+        Err(err) => ctx.error(&format!("write error: {err}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TestShell;
+
+    // a shell and the working directory as dirs shows it.
+    fn shell() -> (TestShell, String) {
+        let mut shell = TestShell::new();
+
+        let cwd = shell.run(&["dirs"]).0.trim_end().to_owned();
+
+        (shell, cwd)
+    }
+
+    #[test]
+    fn pushd_puts_directories_on_top() {
+        let (mut shell, cwd) = shell();
+
+        shell.run(&["pushd", "-n", "/a"]);
+
+        let (stdout, _, status) = shell.run(&["pushd", "-n", "/b"]);
+
+        assert_eq!(status, 0);
+        assert_eq!(stdout, format!("{cwd} /b /a\n"));
+        assert_eq!(
+            shell.run(&["dirs", "-v"]).0,
+            format!(" 0  {cwd}\n 1  /b\n 2  /a\n")
+        );
+        assert_eq!(shell.run(&["dirs", "-p"]).0, format!("{cwd}\n/b\n/a\n"));
+        assert_eq!(shell.run(&["dirs", "+1"]).0, "/b\n");
+        assert_eq!(shell.run(&["dirs", "-0"]).0, "/a\n");
+    }
+
+    #[test]
+    fn popd_takes_directories_from_the_top() {
+        let (mut shell, cwd) = shell();
+
+        for dir in ["/a", "/b", "/c"] {
+            shell.run(&["pushd", "-n", dir]);
+        }
+
+        assert_eq!(shell.run(&["popd", "-n"]).0, format!("{cwd} /b /a\n"));
+        assert_eq!(shell.run(&["popd", "-n", "-0"]).0, format!("{cwd} /b\n"));
+        assert_eq!(shell.run(&["popd", "-n", "+1"]).0, format!("{cwd}\n"));
+
+        let (_, stderr, status) = shell.run(&["popd", "-n"]);
+
+        assert_eq!(status, 1);
+        assert_eq!(stderr, "eash: popd: directory stack empty\n");
+    }
+
+    #[test]
+    fn indexes_out_of_range_are_errors() {
+        let (mut shell, _) = shell();
+
+        shell.run(&["pushd", "-n", "/a"]);
+
+        let (_, stderr, status) = shell.run(&["dirs", "+2"]);
+
+        assert_eq!(status, 1);
+        assert_eq!(
+            stderr,
+            "eash: dirs: +2: directory stack index out of range\n"
+        );
+        assert_eq!(shell.run(&["dirs", "-c"]).2, 0);
+        assert_eq!(shell.run(&["dirs"]).0.split(' ').count(), 1);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::escape;
+use std::io::Write;
+
+pub struct Echo;
+
+impl Builtin for Echo {
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "echo [-neE] [arg...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Write the arguments separated by spaces and followed by a newline. -n leaves \
+         the newline out, -e interprets backslash escapes and -E does not."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-n", "leave the newline out"),
+            ("-e", "interpret backslash escapes"),
+            ("-E", "do not interpret backslash escapes"),
+        ]
+    }
+
+    fn has_help(&self) -> bool {
+        false
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = ctx.args();
+
+        let (mut newline, mut escapes) = (true, false);
+
+        // only words made of known flags are flags, anything else is printed.
+        let mut start = 0;
+
+        for arg in args {
+            let is_flag = arg.len() > 1
+                && arg.starts_with('-')
+                && arg[1..].chars().all(|ch| matches!(ch, 'n' | 'e' | 'E'));
+
+            if is_flag == false {
+                break;
+            }
+
+            for flag in arg[1..].chars() {
+                match flag {
+                    'n' => newline = false,
+                    'e' => escapes = true,
+                    _ => escapes = false,
+                }
+            }
+
+            start += 1;
+        }
+
+        let mut output = Vec::new();
+
+        for (i, arg) in args[start..].iter().enumerate() {
+            if i != 0 {
+                output.push(b' ');
+            }
+
+            if escapes == false {
+                output.extend(arg.as_bytes());
+                continue;
+            }
+
+            let (bytes, is_continued) = escape::unescape(arg, true);
+
+            output.extend(bytes);
+
+            if is_continued == false {
+                newline = false;
+                break;
+            }
+        }
+
+        if newline {
+            output.push(b'\n');
+        }
+
+        match ctx.stdout.write_all(&output) {
+            Ok(_) => 0,
+            Err(err) => ctx.error(&format!("write error: {err}")),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::io::Fd;
+use std::env;
+use std::io;
+use std::io::Write;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::unix::process::ExitStatusExt;
+use std::process::Stdio;
+
+pub struct Env;
+
+impl Builtin for Env {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "env [-i] [-u name] [name=value ...] [command [arg ...]]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run command with the environment changed: -i starts from an empty one, \
+         -u removes name and each name=value sets a variable. Without a command, \
+         print the resulting environment."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-i", "start from an empty environment"),
+            ("-u name", "remove name from the environment"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = ctx.args().to_vec();
+
+        let mut is_empty = false;
+
+        let mut removed = Vec::new();
+
+        let mut i = 0;
+
+        while i < args.len() {
+            match args[i].as_str() {
+                "-i" | "-" => is_empty = true,
+                "-u" => match args.get(i + 1) {
+                    Some(name) => {
+                        removed.push(name.clone());
+                        i += 1;
+                    }
+                    None => return ctx.error("-u: option requires an argument"),
+                },
+                "--" => {
+                    i += 1;
+                    break;
+                }
+                arg if arg.starts_with('-') => {
+                    return ctx.error(&format!("{arg}: invalid option"));
+                }
+                _ => break,
+            }
+
+            i += 1;
+        }
+
+        let mut variables = match is_empty {
+            true => Vec::new(),
+            false => env::vars()
+                .filter(|(name, _)| removed.contains(name) == false)
+                .collect::<Vec<(String, String)>>(),
+        };
+
+        while let Some((name, value)) = args.get(i).and_then(|arg| arg.split_once('=')) {
+            variables.retain(|(other, _)| other != name);
+            variables.push((name.to_owned(), value.to_owned()));
+            i += 1;
+        }
+
+        let argv = &args[i..];
+
+        if argv.is_empty() {
+            let output = variables
+                .iter()
+                .map(|(name, value)| format!("{name}={value}\n"))
+                .collect::<String>();
+
+            return match ctx.stdout.write_all(output.as_bytes()) {
+                Ok(_) => 0,
+                Err(err) => ctx.error(&format!("write error: {err}")),
+            };
+        }
+
+        match execute(argv, &variables, ctx.io()) {
+            Ok(status) => status,
+            Err(err) => {
+                let status = match err.kind() {
+                    io::ErrorKind::NotFound => 127,
+                    _ => 126,
+                };
+
+                ctx.error(&format!("{}: {err}", argv[0]));
+
+                status
+            }
+        }
+    }
+}
+
+// runs a program with exactly the given environment and waits for it.
+fn execute(argv: &[String], variables: &[(String, String)], io: [Fd; 3]) -> io::Result<i32> {
+    let mut command = std::process::Command::new(&argv[0]);
+
+    command.args(&argv[1..]);
+    command.env_clear();
+    command.envs(variables.iter().cloned());
+
+    command.stdin(stdio(io[0])?);
+    command.stdout(stdio(io[1])?);
+    command.stderr(stdio(io[2])?);
+
+    let status = command.spawn()?.wait()?;
+
+    Ok(match (status.code(), status.signal()) {
+        (Some(code), _) => code,
+        (None, Some(signal)) => 128 + signal,
+        (None, None) => 1,
+    })
+}
+
+// a duplicate of the fd, the child closes its own copy.
+fn stdio(fd: Fd) -> io::Result<Stdio> {
+    if fd.raw() < 0 {
+        return Ok(Stdio::null());
+    }
+
+    match unsafe { libc::fcntl(fd.raw(), libc::F_DUPFD_CLOEXEC, 3) } {
+        -1 => Err(io::Error::last_os_error()),
+        fd => Ok(Stdio::from(unsafe { OwnedFd::from_raw_fd(fd) })),
+    }
+}
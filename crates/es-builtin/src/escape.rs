@@ -0,0 +1,95 @@
+// decodes backslash escapes, as bytes since \xHH and octal escapes may not be
+// utf-8. \c ends the output, the returned flag is false then. echo style octal
+// escapes start with \0, printf style ones with any octal digit.
+pub fn unescape(string: &str, is_echo: bool) -> (Vec<u8>, bool) {
+    let bytes = string.as_bytes();
+
+    let mut output = Vec::new();
+
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'\\' || i + 1 == bytes.len() {
+            output.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+
+        i += 1;
+
+        let escape = bytes[i];
+
+        i += 1;
+
+        match escape {
+            b'a' => output.push(0x07),
+            b'b' => output.push(0x08),
+            b'e' | b'E' => output.push(0x1b),
+            b'f' => output.push(0x0c),
+            b'n' => output.push(b'\n'),
+            b'r' => output.push(b'\r'),
+            b't' => output.push(b'\t'),
+            b'v' => output.push(0x0b),
+            b'\\' => output.push(b'\\'),
+            b'"' if is_echo == false => output.push(b'"'),
+            b'\'' if is_echo == false => output.push(b'\''),
+            b'c' => return (output, false),
+
+            b'x' => match digits(&bytes[i..], 16, 2) {
+                (0, _) => output.extend(b"\\x"),
+                (count, value) => {
+                    output.push(value as u8);
+                    i += count;
+                }
+            },
+
+            b'0'..=b'7' => {
+                // echo wants \0nnn, the leading zero is not a digit of the value.
+                let (start, max) = match (is_echo, escape) {
+                    (true, b'0') => (i, 3),
+                    (true, _) => {
+                        output.extend([b'\\', escape]);
+                        continue;
+                    }
+                    (false, _) => (i - 1, 3),
+                };
+
+                let (count, value) = digits(&bytes[start..], 8, max);
+
+                output.push(value as u8);
+
+                i = start + count;
+            }
+
+            _ => output.extend([b'\\', escape]),
+        }
+    }
+
+    (output, true)
+}
+
+// value of up to max leading digits in radix, and how many there were.
+fn digits(bytes: &[u8], radix: u32, max: usize) -> (usize, u32) {
+    let mut value = 0;
+
+    let mut count = 0;
+
+    for byte in bytes.iter().take(max) {
+        match (*byte as char).to_digit(radix) {
+            Some(digit) => {
+                value = value * radix + digit;
+                count += 1;
+            }
+            None => break,
+        }
+    }
+
+    (count, value)
+}
+
+// a string as eash reads it back as one word: plain words as they are,
+// anything else in double quotes. there are no escapes, so a string with a `"`
+// in it is left as it is.
+pub fn quote(string: &str) -> String {
+    es_ast::print::quote_argument(string)
+}
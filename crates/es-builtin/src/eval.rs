@@ -0,0 +1,27 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+
+pub struct Eval;
+
+impl Builtin for Eval {
+    fn name(&self) -> &'static str {
+        "eval"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "eval [arg ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Join the arguments with spaces and run the result as commands in the \
+         current shell."
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let source = ctx.args().join(" ");
+
+        let io = ctx.io();
+
+        ctx.shell.source("eval", &source, None, io)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use std::io;
+use std::os::unix::process::CommandExt;
+
+pub struct Exec;
+
+impl Builtin for Exec {
+    fn name(&self) -> &'static str {
+        "exec"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "exec [-c] [-a name] [command [arg ...]] [redirection ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Replace the shell with command, which gets the redirections. Without a \
+         command the redirections apply to the shell itself from then on, so \
+         exec 3>file opens fd 3 for the rest of the script. -c runs command with \
+         an empty environment and -a passes name as its argv[0]."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-c", "run command with an empty environment"),
+            ("-a name", "pass name as argv[0]"),
+        ]
+    }
+
+    // the engine has already made the redirections those of the shell.
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = ctx.args().to_vec();
+
+        let mut is_clear = false;
+
+        let mut arg0 = None;
+
+        let mut i = 0;
+
+        while let Some(arg) = args.get(i) {
+            match arg.as_str() {
+                "-c" => is_clear = true,
+                "-a" => match args.get(i + 1) {
+                    Some(name) => {
+                        arg0 = Some(name.clone());
+                        i += 1;
+                    }
+                    None => return ctx.error("-a: option requires an argument"),
+                },
+                "--" => {
+                    i += 1;
+                    break;
+                }
+                arg if arg.len() > 1 && arg.starts_with('-') => {
+                    return ctx.error(&format!("{arg}: invalid option"));
+                }
+                _ => break,
+            }
+
+            i += 1;
+        }
+
+        let argv = &args[i..];
+
+        if argv.is_empty() {
+            return 0;
+        }
+
+        // stdin, stdout and stderr of a function called with redirections.
+        for (target, fd) in ctx.io().iter().enumerate() {
+            let target = target as libc::c_int;
+
+            if fd.raw() != target && unsafe { libc::dup2(fd.raw(), target) } < 0 {
+                return ctx.error(&io::Error::last_os_error().to_string());
+            }
+        }
+
+        let mut command = std::process::Command::new(&argv[0]);
+
+        command.args(&argv[1..]);
+
+        if is_clear {
+            command.env_clear();
+        }
+
+        if let Some(arg0) = arg0 {
+            command.arg0(arg0);
+        }
+
+        // only returns when the command could not be run.
+        let err = command.exec();
+
+        let status = match err.kind() {
+            io::ErrorKind::NotFound => 127,
+            _ => 126,
+        };
+
+        ctx.error(&format!("{}: {err}", argv[0]));
+
+        status
+    }
+}
@@ -0,0 +1,37 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+
+pub struct Exit;
+
+impl Builtin for Exit {
+    fn name(&self) -> &'static str {
+        "exit"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "exit [n]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Exit the shell with status n, or with the status of the last command."
+    }
+
+    // the engine stops once the state asks it to exit.
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let code = match ctx.args() {
+            [] => ctx.state().status(),
+            [code] => match code.parse::<i32>() {
+                Ok(code) => code,
+                Err(_) => {
+                    ctx.error(&format!("{code}: numeric argument required"));
+                    2
+                }
+            },
+            _ => return ctx.error("too many arguments"),
+        };
+
+        ctx.state_mut().exit(code);
+
+        code
+    }
+}
@@ -0,0 +1,231 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::state::is_name;
+
+pub struct Getopts;
+
+impl Builtin for Getopts {
+    fn name(&self) -> &'static str {
+        "getopts"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "getopts optstring name [arg ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Parse the next option of the positional parameters, or of the args \
+         when given, and put its letter in name. optstring lists the option \
+         letters, a letter followed by `:` takes an argument, which goes in \
+         OPTARG. OPTIND is the index of the next argument to look at, set it \
+         back to 1 to start over. An unknown option or a missing argument \
+         sets name to `?` and prints an error, unless optstring starts with \
+         `:`, then OPTARG is the letter and name is `:` for a missing \
+         argument. Returns 1 once there are no more options."
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (optstring, name, args) = match ctx.args() {
+            [optstring, name, args @ ..] => (optstring.clone(), name.clone(), args.to_vec()),
+            _ => return ctx.error("usage: getopts optstring name [arg ...]"),
+        };
+
+        if is_name(&name) == false {
+            return ctx.error(&format!("{name}: not a valid identifier"));
+        }
+
+        let args = match args.is_empty() {
+            true => ctx.state().args().to_vec(),
+            false => args,
+        };
+
+        let (is_silent, optstring) = match optstring.strip_prefix(':') {
+            Some(optstring) => (true, optstring.to_owned()),
+            None => (false, optstring),
+        };
+
+        let mut optind = ctx
+            .state()
+            .variable("OPTIND")
+            .and_then(|optind| optind.parse::<usize>().ok())
+            .unwrap_or(1)
+            .max(1);
+
+        // the next flag within the argument, unless OPTIND was changed since.
+        let mut index = match ctx.state().getopts() {
+            (last, index) if last == optind && index > 0 => index,
+            _ => 1,
+        };
+
+        let arg: Vec<char> = match args.get(optind - 1) {
+            Some(arg) => arg.chars().collect(),
+            None => return finish(ctx, &name, optind),
+        };
+
+        if index == 1 {
+            if arg.len() < 2 || arg[0] != '-' {
+                return finish(ctx, &name, optind);
+            }
+
+            if arg == ['-', '-'] {
+                return finish(ctx, &name, optind + 1);
+            }
+        }
+
+        let flag = arg.get(index).copied().unwrap_or('-');
+
+        index += 1;
+
+        let mut value = None;
+        let mut letter = flag.to_string();
+
+        match optstring.find(flag).filter(|_| flag != ':') {
+            None => {
+                letter = "?".to_owned();
+
+                if is_silent {
+                    value = Some(flag.to_string());
+                } else {
+                    ctx.error(&format!("illegal option -- {flag}"));
+                }
+            }
+            Some(i) if optstring[i + flag.len_utf8()..].starts_with(':') => {
+                if index < arg.len() {
+                    value = Some(arg[index..].iter().collect());
+                    index = arg.len();
+                } else if let Some(next) = args.get(optind) {
+                    value = Some(next.clone());
+                    optind += 1;
+                } else if is_silent {
+                    letter = ":".to_owned();
+                    value = Some(flag.to_string());
+                } else {
+                    letter = "?".to_owned();
+                    ctx.error(&format!("option requires an argument -- {flag}"));
+                }
+            }
+            Some(_) => {}
+        }
+
+        if index >= arg.len() {
+            optind += 1;
+            index = 1;
+        }
+
+        let state = ctx.state_mut();
+
+        let assigned = match value {
+            Some(value) => state.assign("OPTARG", value),
+            None => {
+                state.unset_variable("OPTARG");
+                Ok(())
+            }
+        };
+
+        state.set_getopts(optind, index);
+
+        let assigned = assigned
+            .and_then(|_| state.assign("OPTIND", optind.to_string()))
+            .and_then(|_| state.assign(&name, letter));
+
+        match assigned {
+            Ok(()) => 0,
+            Err(message) => ctx.error(&message),
+        }
+    }
+}
+
+// no options left: name is `?` and OPTIND the first operand.
+fn finish(ctx: &mut Context, name: &str, optind: usize) -> i32 {
+    let state = ctx.state_mut();
+
+    state.set_getopts(optind, 1);
+
+    let assigned = state
+        .assign("OPTIND", optind.to_string())
+        .and_then(|_| state.assign(name, "?".to_owned()));
+
+    match assigned {
+        Ok(()) => 1,
+        Err(message) => ctx.error(&message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shell::Shell;
+    use crate::testing::TestShell;
+
+    // runs getopts until it returns 1, the letters and OPTARGs it set.
+    fn parse(shell: &mut TestShell, optstring: &str, args: &[&str]) -> Vec<(String, String)> {
+        let mut argv = vec!["getopts", optstring, "opt"];
+
+        argv.extend(args);
+
+        let mut parsed = Vec::new();
+
+        while shell.run(&argv).2 == 0 {
+            let state = shell.state();
+
+            parsed.push((
+                state.variable("opt").unwrap_or_default(),
+                state.variable("OPTARG").unwrap_or_default(),
+            ));
+        }
+
+        parsed
+    }
+
+    fn pair(letter: &str, value: &str) -> (String, String) {
+        (letter.to_owned(), value.to_owned())
+    }
+
+    #[test]
+    fn options_take_grouped_and_separate_arguments() {
+        let mut shell = TestShell::new();
+
+        assert_eq!(
+            parse(&mut shell, "ab:c", &["-ac", "-bx", "-b", "y", "z", "-c"]),
+            [pair("a", ""), pair("c", ""), pair("b", "x"), pair("b", "y")]
+        );
+        assert_eq!(shell.state().variable("OPTIND").as_deref(), Some("5"));
+        assert_eq!(shell.state().variable("opt").as_deref(), Some("?"));
+    }
+
+    #[test]
+    fn double_dash_ends_the_options() {
+        let mut shell = TestShell::new();
+
+        assert_eq!(parse(&mut shell, "a", &["-a", "--", "-a"]), [pair("a", "")]);
+        assert_eq!(shell.state().variable("OPTIND").as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn errors_are_reported_unless_silent() {
+        let mut shell = TestShell::new();
+
+        assert_eq!(
+            shell.run(&["getopts", "a:", "opt", "-x"]),
+            ("".into(), "eash: getopts: illegal option -- x\n".into(), 0)
+        );
+        assert_eq!(shell.state().variable("opt").as_deref(), Some("?"));
+
+        shell.state_mut().set_variable("OPTIND", "1".to_owned());
+
+        assert_eq!(
+            shell.run(&["getopts", "a:", "opt", "-a"]).1,
+            "eash: getopts: option requires an argument -- a\n"
+        );
+
+        shell.state_mut().set_variable("OPTIND", "1".to_owned());
+
+        assert_eq!(
+            parse(&mut shell, ":a:", &["-x", "-a"]),
+            [pair("?", "x"), pair(":", "a")]
+        );
+        assert_eq!(
+            shell.run(&["getopts", "a", "1bad"]).1,
+            "eash: getopts: 1bad: not a valid identifier\n"
+        );
+    }
+}
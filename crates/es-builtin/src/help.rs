@@ -0,0 +1,119 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::options;
+use std::io::Write;
+
+// the column the descriptions are wrapped at.
+const WIDTH: usize = 80;
+
+pub struct Help;
+
+impl Builtin for Help {
+    fn name(&self) -> &'static str {
+        "help"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "help [-s] [name ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Describe each builtin name: its synopsis, what it does and its \
+         options. Without names, list the synopsis of every builtin. \
+         `name --help` prints the same for most builtins."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-s", "print only the synopsis")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, names) = match options::short_flags(ctx.args(), "s") {
+            Ok((flags, names)) => (flags, names.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        if names.is_empty() {
+            let mut text = String::from("builtins, `help name` tells more about one:\n\n");
+
+            for builtin in ctx.shell.registry().iter() {
+                text.push_str(&format!("  {}\n", builtin.synopsis()));
+            }
+
+            let _ = write!(ctx.stdout, "{text}");
+
+            return 0;
+        }
+
+        let mut status = 0;
+
+        for name in &names {
+            let text = match ctx.shell.registry().get(name) {
+                Some(builtin) if flags.contains(&'s') => format!("{}\n", builtin.synopsis()),
+                Some(builtin) => text(builtin.as_ref()),
+                None => {
+                    status = ctx.error(&format!("{name}: no such builtin"));
+                    continue;
+                }
+            };
+
+            let _ = write!(ctx.stdout, "{text}");
+        }
+
+        status
+    }
+}
+
+// the synopsis, then the description and the options indented under it.
+pub fn text(builtin: &dyn Builtin) -> String {
+    let mut text = format!("{}\n\n", builtin.synopsis());
+
+    for line in wrap(builtin.description(), WIDTH - 4) {
+        text.push_str(&format!("    {line}\n"));
+    }
+
+    let options = builtin.options();
+
+    if options.is_empty() {
+        return text;
+    }
+
+    text.push_str("\n    options:\n");
+
+    let width = options
+        .iter()
+        .map(|(option, _)| option.len())
+        .max()
+        .unwrap_or(0);
+
+    for (option, description) in options {
+        text.push_str(&format!("      {option:width$}  {description}\n"));
+    }
+
+    text
+}
+
+// the words of string in lines of at most width columns, longer words alone.
+fn wrap(string: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    let mut line = String::new();
+
+    for word in string.split_whitespace() {
+        if line.is_empty() == false && line.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+
+        if line.is_empty() == false {
+            line.push(' ');
+        }
+
+        line.push_str(word);
+    }
+
+    if line.is_empty() == false {
+        lines.push(line);
+    }
+
+    lines
+}
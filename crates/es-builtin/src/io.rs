@@ -0,0 +1,69 @@
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::os::fd::RawFd;
+
+// a file descriptor of the shell handed to a builtin. it is borrowed, dropping it
+// never closes the descriptor, and reads and writes are not buffered.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Fd(RawFd);
+
+impl Fd {
+    pub const STDIN: Fd = Fd(0);
+
+    pub const STDOUT: Fd = Fd(1);
+
+    pub const STDERR: Fd = Fd(2);
+
+    pub fn new(fd: RawFd) -> Self {
+        Self(fd)
+    }
+
+    pub fn raw(&self) -> RawFd {
+        self.0
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        unsafe { libc::isatty(self.0) == 1 }
+    }
+}
+
+impl Read for Fd {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = unsafe { libc::read(self.0, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+
+            if n >= 0 {
+                return Ok(n as usize);
+            }
+
+            let err = io::Error::last_os_error();
+
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+}
+
+impl Write for Fd {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        loop {
+            let n = unsafe { libc::write(self.0, buf.as_ptr() as *const libc::c_void, buf.len()) };
+
+            if n >= 0 {
+                return Ok(n as usize);
+            }
+
+            let err = io::Error::last_os_error();
+
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
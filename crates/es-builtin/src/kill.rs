@@ -0,0 +1,136 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::signal;
+use std::io;
+
+pub struct Kill;
+
+impl Builtin for Kill {
+    fn name(&self) -> &'static str {
+        "kill"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "kill [-s signal | -n number | -signal] pid | %job ... or kill -l [signal | status ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Send a signal, TERM by default, to processes by pid or to background jobs \
+         by %n, %% or %+ for the latest and %- for the one before. Signals are \
+         given by name, with or without SIG, or by number. -l lists the signal \
+         names, or converts between names and numbers, an exit status above 128 \
+         gives the signal that ended the command."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-s signal", "the signal to send, by name"),
+            ("-n number", "the signal to send, by number"),
+            ("-signal", "the signal to send, by name or number"),
+            (
+                "-l",
+                "list signal names or convert between names and numbers",
+            ),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = ctx.args().to_vec();
+
+        let (signal, targets) = match args.as_slice() {
+            [flag, rest @ ..] if flag == "-l" || flag == "-L" => return list(ctx, rest),
+            [flag, signal, rest @ ..] if flag == "-s" || flag == "-n" => (signal.as_str(), rest),
+            [flag, ..] if flag == "-s" || flag == "-n" => {
+                return ctx.error(&format!("{flag}: option requires an argument"))
+            }
+            [flag, rest @ ..] if flag == "--" => ("TERM", rest),
+            [flag, rest @ ..] if flag.len() > 1 && flag.starts_with('-') => (&flag[1..], rest),
+            _ => ("TERM", &args[..]),
+        };
+
+        let signal = match signal::number(signal) {
+            Some(number) if number != 0 || signal == "0" => number,
+            _ => return ctx.error(&format!("{signal}: invalid signal specification")),
+        };
+
+        if targets.is_empty() {
+            return ctx.error("usage: kill [-s signal | -n number | -signal] pid | %job ...");
+        }
+
+        let mut status = 0;
+
+        for target in targets {
+            let pid = match target.strip_prefix('%') {
+                Some(job) => match ctx.shell.job(job) {
+                    Some(pid) => pid,
+                    None => {
+                        status = ctx.error(&format!("{target}: no such job"));
+                        continue;
+                    }
+                },
+                None => match target.parse::<libc::pid_t>() {
+                    Ok(pid) => pid,
+                    Err(_) => {
+                        status =
+                            ctx.error(&format!("{target}: arguments must be process or job IDs"));
+                        continue;
+                    }
+                },
+            };
+
+            if unsafe { libc::kill(pid, signal) } == -1 {
+                let err = io::Error::last_os_error();
+
+                status = ctx.error(&format!("({pid}) - {err}"));
+            }
+        }
+
+        status
+    }
+}
+
+// every name without arguments, otherwise names for numbers and statuses and
+// numbers for names.
+fn list(ctx: &mut Context, args: &[String]) -> i32 {
+    if args.is_empty() {
+        let output = signal::SIGNALS
+            .iter()
+            .filter(|(_, number)| *number != 0)
+            .map(|(name, _)| format!("{name}\n"))
+            .collect::<String>();
+
+        return ctx.write(&output);
+    }
+
+    let mut output = String::new();
+
+    let mut status = 0;
+
+    for arg in args {
+        let line = match arg.parse::<libc::c_int>() {
+            Ok(number) => {
+                let number = match number > 128 {
+                    true => number - 128,
+                    false => number,
+                };
+
+                signal::name(number)
+                    .filter(|_| number != 0)
+                    .map(|name| name.to_owned())
+            }
+            Err(_) => signal::number(arg)
+                .filter(|number| *number != 0)
+                .map(|number| number.to_string()),
+        };
+
+        match line {
+            Some(line) => output.push_str(&format!("{line}\n")),
+            None => status = ctx.error(&format!("{arg}: invalid signal specification")),
+        }
+    }
+
+    match ctx.write(&output) {
+        0 => status,
+        err => err,
+    }
+}
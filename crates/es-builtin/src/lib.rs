@@ -1,22 +1,53 @@
-use std::env;
-use std::io;
-use std::path::Path;
-use std::process;
+pub mod builtin;
+pub mod compare;
+pub mod escape;
+pub mod help;
+pub mod io;
+pub mod options;
+pub mod path;
+pub mod registry;
+pub mod setting;
+pub mod shell;
+pub mod signal;
+pub mod state;
 
-pub fn exit(code: i32) {
-    process::exit(code)
-}
+mod abort;
+mod alias;
+mod argparse;
+mod boolean;
+mod builtin_command;
+mod cd;
+mod command;
+mod dirs;
+mod echo;
+mod env;
+mod eval;
+mod exec;
+mod exit;
+mod getopts;
+mod kill;
+mod printf;
+mod pwd;
+mod read;
+mod set;
+mod shift;
+mod source;
+mod test;
+mod trap;
+mod r#type;
+mod ulimit;
+mod umask;
+mod variable;
+mod wait;
+mod which;
 
-pub fn abort() {
-    process::abort()
-}
+#[cfg(test)]
+mod testing;
 
-pub fn cd(string: &str) -> io::Result<()> {
-    let path = Path::new(string);
-
-    env::set_current_dir(&path)?;
-
-    env::set_var("PWD", path);
-
-    Ok(())
-}
+pub use builtin::Builtin;
+pub use builtin::Context;
+pub use registry::Registry;
+pub use setting::Setting;
+pub use shell::Lookup;
+pub use shell::Shell;
+pub use state::State;
@@ -0,0 +1,30 @@
+// splits leading short flags such as `-a -bc` off the arguments. `--` ends the
+// flags, so does the first argument not starting with `-` or a lone `-`.
+// returns the flags in order and the remaining arguments, or the first flag
+// not in allowed.
+pub fn short_flags<'a>(
+    args: &'a [String],
+    allowed: &str,
+) -> Result<(Vec<char>, &'a [String]), char> {
+    let mut flags = Vec::new();
+
+    for (i, arg) in args.iter().enumerate() {
+        if arg == "--" {
+            return Ok((flags, &args[i + 1..]));
+        }
+
+        if arg.len() < 2 || arg.starts_with('-') == false {
+            return Ok((flags, &args[i..]));
+        }
+
+        for flag in arg[1..].chars() {
+            if allowed.contains(flag) == false {
+                return Err(flag);
+            }
+
+            flags.push(flag);
+        }
+    }
+
+    Ok((flags, &[]))
+}
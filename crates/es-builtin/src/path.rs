@@ -0,0 +1,134 @@
+use crate::dirs;
+use crate::shell::Shell;
+use crate::state::State;
+use std::env;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+// what a command name stands for.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Kind {
+    Alias(String),
+    Function,
+    Builtin,
+    File(PathBuf),
+}
+
+// the meanings of a name in the order the engine tries them. only the first
+// one unless all is set.
+pub fn resolve(shell: &dyn Shell, name: &str, all: bool) -> Vec<Kind> {
+    let mut kinds = Vec::new();
+
+    if let Some(text) = shell.state().alias(name) {
+        kinds.push(Kind::Alias(text.to_owned()));
+    }
+
+    if shell.state().function(name).is_some() {
+        kinds.push(Kind::Function);
+    }
+
+    if shell.registry().contains(name) {
+        kinds.push(Kind::Builtin);
+    }
+
+    if all || kinds.is_empty() {
+        let path = shell.state().variable("PATH").unwrap_or_default();
+
+        for file in search(name, &path) {
+            kinds.push(Kind::File(file));
+
+            if all == false {
+                break;
+            }
+        }
+    }
+
+    if all == false {
+        kinds.truncate(1);
+    }
+
+    kinds
+}
+
+// executables named name in the directories of path, a name with a slash is
+// taken as it is.
+pub fn search(name: &str, path: &str) -> Vec<PathBuf> {
+    if name.contains('/') {
+        return match is_executable(Path::new(name)) {
+            true => vec![PathBuf::from(name)],
+            false => Vec::new(),
+        };
+    }
+
+    env::split_paths(path)
+        .map(|dir| match dir.as_os_str().is_empty() {
+            true => PathBuf::from(".").join(name),
+            false => dir.join(name),
+        })
+        .filter(|file| is_executable(file))
+        .collect()
+}
+
+pub fn is_executable(path: &Path) -> bool {
+    match fs::metadata(path) {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+// a leading ~ of a word: ~ and ~/x are HOME, ~user the home of user, ~+ and
+// ~- PWD and OLDPWD, ~N, ~+N and ~-N entries of the directory stack. the word
+// is kept when there is nothing to expand to.
+pub fn tilde(state: &State, word: &str) -> String {
+    let rest = match word.strip_prefix('~') {
+        Some(rest) => rest,
+        None => return word.to_owned(),
+    };
+
+    let (prefix, suffix) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, ""),
+    };
+
+    let digits = prefix.strip_prefix(['+', '-']).unwrap_or(prefix);
+
+    let is_index = digits.is_empty() == false && digits.chars().all(|ch| ch.is_ascii_digit());
+
+    let dir = match prefix {
+        "" => state.variable("HOME").or_else(|| home(None)),
+        "+" => state.variable("PWD"),
+        "-" => state.variable("OLDPWD"),
+        _ if is_index => dirs::entry(state, prefix),
+        user => home(Some(user)),
+    };
+
+    match dir {
+        Some(dir) => format!("{dir}{suffix}"),
+        None => word.to_owned(),
+    }
+}
+
+// the home directory from the password database, of the current user
+// without a name.
+fn home(user: Option<&str>) -> Option<String> {
+    let entry = match user {
+        Some(user) => {
+            let name = CString::new(user).ok()?;
+
+            unsafe { libc::getpwnam(name.as_ptr()) }
+        }
+        None => unsafe { libc::getpwuid(libc::getuid()) },
+    };
+
+    if entry.is_null() {
+        return None;
+    }
+
+    let dir = unsafe { CStr::from_ptr((*entry).pw_dir) };
+
+    Some(dir.to_string_lossy().into_owned())
+}
@@ -0,0 +1,617 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::escape;
+use std::io::Write;
+
+pub struct Printf;
+
+impl Builtin for Printf {
+    fn name(&self) -> &'static str {
+        "printf"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "printf [-v var] format [arg...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Write the arguments as the format says. The format is reused while \
+         arguments are left. Supports the escapes of echo -e and the directives \
+         %s %b %c %d %i %u %o %x %X %f %F %e %E %g %G and %%, with flags, width \
+         and precision. -v assigns the output to var instead."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-v var", "assign the output to var")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (var, args) = match ctx.args() {
+            [flag, var, args @ ..] if flag == "-v" => (Some(var.to_owned()), args.to_vec()),
+            [flag, args @ ..] if flag == "--" => (None, args.to_vec()),
+            args => (None, args.to_vec()),
+        };
+
+        let (format, args) = match args.split_first() {
+            Some((format, args)) => (format, args),
+            None => return ctx.error("usage: printf [-v var] format [arg...]"),
+        };
+
+        let mut formatter = Formatter {
+            args: args,
+            next: 0,
+            errors: Vec::new(),
+        };
+
+        let output = formatter.format(format);
+
+        let mut status = 0;
+
+        for err in formatter.errors {
+            status = ctx.error(&err);
+        }
+
+        match var {
+            Some(var) => {
+                let value = String::from_utf8_lossy(&output).into_owned();
+
+                if let Err(err) = ctx.state_mut().assign(&var, value) {
+                    return ctx.error(&err);
+                }
+            }
+            None => {
+                if let Err(err) = ctx.stdout.write_all(&output) {
+                    return ctx.error(&format!("write error: {err}"));
+                }
+            }
+        }
+
+        status
+    }
+}
+
+// a conversion directive, % flags width .precision conversion.
+#[derive(Default)]
+struct Spec {
+    left: bool,
+    plus: bool,
+    space: bool,
+    zero: bool,
+    alternate: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+struct Formatter<'a> {
+    args: &'a [String],
+    next: usize,
+    errors: Vec<String>,
+}
+
+impl Formatter<'_> {
+    fn format(&mut self, format: &str) -> Vec<u8> {
+        let mut output = Vec::new();
+
+        loop {
+            let start = self.next;
+
+            if self.format_once(format, &mut output) == false {
+                break;
+            }
+
+            // the format is reused only while it takes arguments.
+            if self.next >= self.args.len() || self.next == start {
+                break;
+            }
+        }
+
+        output
+    }
+
+    // returns false when \c ended the output.
+    fn format_once(&mut self, format: &str, output: &mut Vec<u8>) -> bool {
+        let chars = format.chars().collect::<Vec<char>>();
+
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '\\' => {
+                    let rest = chars[i..].iter().collect::<String>();
+
+                    let length = escape_length(&rest);
+
+                    let escape = rest.chars().take(length).collect::<String>();
+
+                    let (bytes, is_continued) = escape::unescape(&escape, false);
+
+                    output.extend(bytes);
+
+                    if is_continued == false {
+                        return false;
+                    }
+
+                    i += length;
+                }
+
+                '%' if chars.get(i + 1) == Some(&'%') => {
+                    output.push(b'%');
+                    i += 2;
+                }
+
+                '%' => {
+                    i += 1;
+
+                    let mut spec = Spec::default();
+
+                    while let Some(flag) = chars.get(i) {
+                        match flag {
+                            '-' => spec.left = true,
+                            '+' => spec.plus = true,
+                            ' ' => spec.space = true,
+                            '0' => spec.zero = true,
+                            '#' => spec.alternate = true,
+                            _ => break,
+                        }
+
+                        i += 1;
+                    }
+
+                    spec.width = self.number(&chars, &mut i, &mut spec.left);
+
+                    if chars.get(i) == Some(&'.') {
+                        i += 1;
+
+                        spec.precision = Some(self.number(&chars, &mut i, &mut false));
+                    }
+
+                    let conversion = match chars.get(i) {
+                        Some(conversion) => *conversion,
+                        None => {
+                            self.errors.push("missing format character".to_owned());
+                            return true;
+                        }
+                    };
+
+                    i += 1;
+
+                    if self.convert(conversion, &spec, output) == false {
+                        return false;
+                    }
+                }
+
+                ch => {
+                    let mut buf = [0; 4];
+
+                    output.extend(ch.encode_utf8(&mut buf).as_bytes());
+
+                    i += 1;
+                }
+            }
+        }
+
+        true
+    }
+
+    // a width or precision: digits, or `*` to take it from the arguments. a
+    // negative `*` width means left alignment.
+    fn number(&mut self, chars: &[char], i: &mut usize, left: &mut bool) -> usize {
+        if chars.get(*i) == Some(&'*') {
+            *i += 1;
+
+            let number = self.integer();
+
+            if number < 0 {
+                *left = true;
+            }
+
+            return number.unsigned_abs() as usize;
+        }
+
+        let mut number = 0;
+
+        while let Some(digit) = chars.get(*i).and_then(|ch| ch.to_digit(10)) {
+            number = number * 10 + digit as usize;
+            *i += 1;
+        }
+
+        number
+    }
+
+    fn arg(&mut self) -> Option<&str> {
+        let arg = self.args.get(self.next)?;
+
+        self.next += 1;
+
+        Some(arg)
+    }
+
+    fn integer(&mut self) -> i64 {
+        let arg = self.arg().unwrap_or_default().to_owned();
+
+        match parse_integer(&arg) {
+            Some(number) => number,
+            None => {
+                self.errors.push(format!("{arg}: invalid number"));
+                0
+            }
+        }
+    }
+
+    fn float(&mut self) -> f64 {
+        let arg = self.arg().unwrap_or_default().trim().to_owned();
+
+        if arg.is_empty() {
+            return 0.0;
+        }
+
+        match arg.parse::<f64>() {
+            Ok(number) => number,
+            Err(_) => match parse_integer(&arg) {
+                Some(number) => number as f64,
+                None => {
+                    self.errors.push(format!("{arg}: invalid number"));
+                    0.0
+                }
+            },
+        }
+    }
+
+    // returns false when \c in a %b argument ended the output.
+    fn convert(&mut self, conversion: char, spec: &Spec, output: &mut Vec<u8>) -> bool {
+        let (body, is_numeric) = match conversion {
+            's' => {
+                let arg = self.arg().unwrap_or_default();
+
+                let body = match spec.precision {
+                    Some(precision) => arg.chars().take(precision).collect(),
+                    None => arg.to_owned(),
+                };
+
+                (body.into_bytes(), false)
+            }
+
+            'b' => {
+                let arg = self.arg().unwrap_or_default().to_owned();
+
+                let (mut bytes, is_continued) = escape::unescape(&arg, true);
+
+                if let Some(precision) = spec.precision {
+                    bytes.truncate(precision);
+                }
+
+                output.extend(pad(bytes, spec, false));
+
+                return is_continued;
+            }
+
+            'c' => {
+                let body = self
+                    .arg()
+                    .unwrap_or_default()
+                    .chars()
+                    .take(1)
+                    .collect::<String>();
+
+                (body.into_bytes(), false)
+            }
+
+            'd' | 'i' => {
+                let number = self.integer();
+
+                let digits = with_precision(number.unsigned_abs().to_string(), spec.precision);
+
+                (signed(number < 0, digits, spec).into_bytes(), true)
+            }
+
+            'u' | 'o' | 'x' | 'X' => {
+                let number = self.integer() as u64;
+
+                let digits = match conversion {
+                    'u' => number.to_string(),
+                    'o' => format!("{number:o}"),
+                    'x' => format!("{number:x}"),
+                    _ => format!("{number:X}"),
+                };
+
+                let mut digits = with_precision(digits, spec.precision);
+
+                if spec.alternate && number != 0 {
+                    match conversion {
+                        'o' if digits.starts_with('0') == false => digits.insert(0, '0'),
+                        'x' => digits.insert_str(0, "0x"),
+                        'X' => digits.insert_str(0, "0X"),
+                        _ => {}
+                    }
+                }
+
+                (digits.into_bytes(), true)
+            }
+
+            'f' | 'F' | 'e' | 'E' | 'g' | 'G' => {
+                let number = self.float();
+
+                let precision = spec.precision.unwrap_or(6);
+
+                let digits = match conversion {
+                    _ if number.is_nan() => "nan".to_owned(),
+                    _ if number.is_infinite() => "inf".to_owned(),
+                    'f' | 'F' => format!("{:.*}", precision, number.abs()),
+                    'e' | 'E' => exponent(number.abs(), precision),
+                    _ => general(number.abs(), precision, spec.alternate),
+                };
+
+                let digits = match conversion.is_ascii_uppercase() {
+                    true => digits.to_uppercase(),
+                    false => digits,
+                };
+
+                let is_negative = number.is_sign_negative() && number.is_nan() == false;
+
+                (
+                    signed(is_negative, digits, spec).into_bytes(),
+                    number.is_finite(),
+                )
+            }
+
+            _ => {
+                self.errors
+                    .push(format!("%{conversion}: invalid directive"));
+                return true;
+            }
+        };
+
+        output.extend(pad(body, spec, is_numeric));
+
+        true
+    }
+}
+
+// integers as sh takes them: decimal, 0x hex, 0 octal, or 'c for the code of c.
+fn parse_integer(arg: &str) -> Option<i64> {
+    let arg = arg.trim();
+
+    if arg.is_empty() {
+        return Some(0);
+    }
+
+    if let Some(ch) = arg
+        .strip_prefix(['\'', '"'])
+        .and_then(|rest| rest.chars().next())
+    {
+        return Some(ch as i64);
+    }
+
+    let (is_negative, digits) = match arg.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, arg.strip_prefix('+').unwrap_or(arg)),
+    };
+
+    let number = if let Some(hex) = digits.strip_prefix("0x").or(digits.strip_prefix("0X")) {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse::<i64>().ok()?
+    };
+
+    Some(match is_negative {
+        true => -number,
+        false => number,
+    })
+}
+
+// at least precision digits, padded with zeros.
+fn with_precision(digits: String, precision: Option<usize>) -> String {
+    match precision {
+        Some(precision) if digits.len() < precision => {
+            format!("{}{digits}", "0".repeat(precision - digits.len()))
+        }
+        _ => digits,
+    }
+}
+
+fn signed(is_negative: bool, digits: String, spec: &Spec) -> String {
+    let sign = match (is_negative, spec.plus, spec.space) {
+        (true, _, _) => "-",
+        (false, true, _) => "+",
+        (false, false, true) => " ",
+        _ => "",
+    };
+
+    format!("{sign}{digits}")
+}
+
+// pads to the width, with zeros after the sign and prefix for numbers.
+fn pad(body: Vec<u8>, spec: &Spec, is_numeric: bool) -> Vec<u8> {
+    let length = String::from_utf8_lossy(&body).chars().count();
+
+    if length >= spec.width {
+        return body;
+    }
+
+    let fill = spec.width - length;
+
+    if spec.left {
+        let mut body = body;
+        body.extend(vec![b' '; fill]);
+        return body;
+    }
+
+    if spec.zero && is_numeric {
+        let prefix = body
+            .iter()
+            .take_while(|byte| matches!(byte, b'-' | b'+' | b' '))
+            .count();
+
+        let prefix = match body[prefix..].starts_with(b"0x") || body[prefix..].starts_with(b"0X") {
+            true => prefix + 2,
+            false => prefix,
+        };
+
+        let mut padded = body[..prefix].to_vec();
+        padded.extend(vec![b'0'; fill]);
+        padded.extend(&body[prefix..]);
+        return padded;
+    }
+
+    let mut padded = vec![b' '; fill];
+    padded.extend(body);
+    padded
+}
+
+// %e: d.ddde+xx, the exponent has a sign and at least two digits.
+fn exponent(number: f64, precision: usize) -> String {
+    let formatted = format!("{:.*e}", precision, number);
+
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+
+    let exponent = exponent.parse::<i32>().unwrap_or(0);
+
+    let sign = match exponent < 0 {
+        true => '-',
+        false => '+',
+    };
+
+    format!("{mantissa}e{sign}{:02}", exponent.abs())
+}
+
+// %g: %e when the exponent is below -4 or not below the precision, %f
+// otherwise, without trailing zeros unless # was given.
+fn general(number: f64, precision: usize, alternate: bool) -> String {
+    let precision = precision.max(1);
+
+    if number == 0.0 {
+        return match alternate {
+            true => format!("{:.*}", precision - 1, 0.0),
+            false => "0".to_owned(),
+        };
+    }
+
+    // the exponent after rounding to precision significant digits.
+    let exponent_form = exponent(number, precision - 1);
+
+    let exponent = exponent_form
+        .split_once('e')
+        .and_then(|(_, exponent)| exponent.parse::<i32>().ok())
+        .unwrap_or(0);
+
+    let formatted = match exponent < -4 || exponent >= precision as i32 {
+        true => exponent_form,
+        false => format!("{:.*}", (precision as i32 - 1 - exponent) as usize, number),
+    };
+
+    if alternate {
+        return formatted;
+    }
+
+    let (mantissa, exponent) = match formatted.split_once('e') {
+        Some((mantissa, exponent)) => (mantissa.to_owned(), format!("e{exponent}")),
+        None => (formatted, String::new()),
+    };
+
+    let mantissa = match mantissa.contains('.') {
+        true => mantissa
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_owned(),
+        false => mantissa,
+    };
+
+    format!("{mantissa}{exponent}")
+}
+
+// how many chars the escape at the start of string takes, printf style.
+fn escape_length(string: &str) -> usize {
+    let chars = string.chars().collect::<Vec<char>>();
+
+    match chars.get(1) {
+        None => 1,
+        Some('0'..='7') => {
+            2 + chars[2..]
+                .iter()
+                .take(2)
+                .take_while(|ch| ch.is_digit(8))
+                .count()
+        }
+        Some('x') => {
+            2 + chars[2..]
+                .iter()
+                .take(2)
+                .take_while(|ch| ch.is_ascii_hexdigit())
+                .count()
+        }
+        Some(_) => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the output and the errors of printf format args.
+    fn printf(format: &str, args: &[&str]) -> (String, Vec<String>) {
+        let args = args
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<String>>();
+
+        let mut formatter = Formatter {
+            args: &args,
+            next: 0,
+            errors: Vec::new(),
+        };
+
+        let output = formatter.format(format);
+
+        (
+            String::from_utf8_lossy(&output).into_owned(),
+            formatter.errors,
+        )
+    }
+
+    #[test]
+    fn directives_take_flags_width_and_precision() {
+        assert_eq!(
+            printf("[%5s|%-5s|%.2s]", &["a", "b", "xyz"]).0,
+            "[    a|b    |xy]"
+        );
+        assert_eq!(
+            printf("%05d %+d % d %.3d", &["-42", "7", "7", "5"]).0,
+            "-0042 +7  7 005"
+        );
+        assert_eq!(
+            printf("%x %X %#x %o %#o", &["255", "255", "255", "8", "8"]).0,
+            "ff FF 0xff 10 010"
+        );
+        assert_eq!(printf("%d %d %d", &["0x10", "010", "'A"]).0, "16 8 65");
+        assert_eq!(printf("%*d|%-*d|", &["4", "1", "3", "2"]).0, "   1|2  |");
+        assert_eq!(
+            printf("%.2f %e %g %g", &["3.14159", "1234.5", "0.0001", "100000"]).0,
+            "3.14 1.234500e+03 0.0001 100000"
+        );
+        assert_eq!(printf("%c%c %u 100%%", &["xyz", "é", "3"]).0, "xé 3 100%");
+    }
+
+    #[test]
+    fn format_is_reused_while_arguments_are_left() {
+        assert_eq!(printf("%s=%s\n", &["a", "1", "b"]).0, "a=1\nb=\n");
+        assert_eq!(printf("x\n", &["unused"]).0, "x\n");
+    }
+
+    #[test]
+    fn escapes_in_the_format_and_in_b() {
+        assert_eq!(printf("a\\tb\\n\\101\\x41\\\\", &[]).0, "a\tb\nAA\\");
+        assert_eq!(printf("%b|%s", &["1\\n2", "1\\n2"]).0, "1\n2|1\\n2");
+        assert_eq!(printf("a\\cb", &[]).0, "a");
+        assert_eq!(printf("%b%s", &["x\\cy", "z"]).0, "x");
+    }
+
+    #[test]
+    fn bad_numbers_and_directives_are_errors() {
+        let (output, errors) = printf("%d %q|", &["abc"]);
+
+        assert_eq!(output, "0 |");
+        assert_eq!(errors, ["abc: invalid number", "%q: invalid directive"]);
+    }
+}
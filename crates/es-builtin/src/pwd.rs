@@ -0,0 +1,76 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::options;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+pub struct Pwd;
+
+impl Builtin for Pwd {
+    fn name(&self) -> &'static str {
+        "pwd"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "pwd [-LP]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print the working directory. -L, the default, keeps the symbolic links \
+         followed to get there, -P resolves them."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-L", "keep the symbolic links, the default"),
+            ("-P", "resolve symbolic links"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let flags = match options::short_flags(ctx.args(), "LP") {
+            Ok((_, args)) if args.is_empty() == false => return ctx.error("too many arguments"),
+            Ok((flags, _)) => flags,
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        let is_physical = flags.last() == Some(&'P');
+
+        let logical = match is_physical {
+            true => None,
+            false => ctx
+                .state()
+                .variable("PWD")
+                .filter(|pwd| is_current_dir(pwd)),
+        };
+
+        let dir = match logical {
+            Some(dir) => dir,
+            None => match env::current_dir() {
+                Ok(dir) => dir.to_string_lossy().into_owned(),
+                Err(err) => return ctx.error(&err.to_string()),
+            },
+        };
+
+        match writeln!(ctx.stdout, "{dir}") {
+            Ok(_) => 0,
+            Err(err) => ctx.error(&format!("write error: {err}")),
+        }
+    }
+}
+
+// PWD may only be trusted when it is absolute and still names the working
+// directory.
+pub fn is_current_dir(path: &str) -> bool {
+    if Path::new(path).is_absolute() == false {
+        return false;
+    }
+
+    match (fs::metadata(path), fs::metadata(".")) {
+        (Ok(path), Ok(current)) => path.dev() == current.dev() && path.ino() == current.ino(),
+        _ => false,
+    }
+}
@@ -0,0 +1,426 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::state;
+use es_terminal::raw::RawMode;
+use std::io;
+use std::io::Read as _;
+use std::io::Write;
+use std::time::Duration;
+use std::time::Instant;
+
+const DEFAULT_IFS: &str = " \t\n";
+
+pub struct Read;
+
+#[derive(Debug, Default)]
+struct Options {
+    is_raw: bool,
+    is_silent: bool,
+    array: Option<String>,
+    delim: Option<u8>,
+    count: Option<usize>,
+    prompt: Option<String>,
+    timeout: Option<Duration>,
+    names: Vec<String>,
+}
+
+// why reading stopped.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum End {
+    Delim,
+    Eof,
+    Timeout,
+}
+
+impl Builtin for Read {
+    fn name(&self) -> &'static str {
+        "read"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "read [-rs] [-a array] [-d delim] [-n count] [-p prompt] [-t timeout] [name ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Read a line from stdin and split it into fields on IFS, one per name with \
+         the rest of the line going to the last one, or into array with -a. \
+         Without names the line is stored in REPLY. -r keeps backslashes, -s does \
+         not echo input from a terminal, -d reads up to delim instead of a newline, \
+         -n reads at most count characters, -p prints prompt first and -t gives up \
+         after timeout seconds. The status is 1 at the end of input and above 128 \
+         on timeout."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-r", "keep backslashes"),
+            ("-s", "do not echo input from a terminal"),
+            ("-a array", "store the fields in array"),
+            ("-d delim", "read up to delim instead of a newline"),
+            ("-n count", "read at most count characters"),
+            ("-p prompt", "print prompt first"),
+            ("-t timeout", "give up after timeout seconds"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let options = match parse(ctx.args()) {
+            Ok(options) => options,
+            Err(err) => return ctx.error(&err),
+        };
+
+        for name in options.names.iter().chain(options.array.iter()) {
+            if state::is_name(name) == false {
+                return ctx.error(&format!("`{name}': not a valid identifier"));
+            }
+        }
+
+        let is_terminal = ctx.stdin.is_terminal();
+
+        if let (Some(prompt), true) = (&options.prompt, is_terminal) {
+            let _ = write!(ctx.stderr, "{prompt}");
+        }
+
+        let (input, quoted, end) = {
+            let delim = options.delim.unwrap_or(b'\n');
+
+            // echo off for -s, line buffering off when the line does not end
+            // where the terminal would end it.
+            let mut flags = 0;
+
+            if options.is_silent {
+                flags |= libc::ECHO;
+            }
+
+            if options.count.is_some() || delim != b'\n' {
+                flags |= libc::ICANON;
+            }
+
+            let _raw_mode = match is_terminal && flags != 0 {
+                true => RawMode::new(ctx.stdin.raw(), flags).ok(),
+                false => None,
+            };
+
+            match read(ctx, &options, delim) {
+                Ok(read) => read,
+                Err(err) => return ctx.error(&err.to_string()),
+            }
+        };
+
+        let line = String::from_utf8_lossy(&input).into_owned();
+
+        let chars = line
+            .char_indices()
+            .map(|(i, ch)| (ch, quoted.contains(&i)))
+            .collect::<Vec<(char, bool)>>();
+
+        let ifs = ctx
+            .state()
+            .variable("IFS")
+            .unwrap_or(DEFAULT_IFS.to_owned());
+
+        let result = match (&options.array, options.names.as_slice()) {
+            (Some(array), _) => {
+                let fields = split(&chars, &ifs, usize::MAX);
+
+                ctx.state_mut().assign_array(array, fields)
+            }
+            (None, []) => ctx.state_mut().assign("REPLY", line),
+            (None, names) => {
+                let mut fields = split(&chars, &ifs, names.len()).into_iter();
+
+                names.iter().try_for_each(|name| {
+                    let field = fields.next().unwrap_or_default();
+
+                    ctx.state_mut().assign(name, field)
+                })
+            }
+        };
+
+        if let Err(err) = result {
+            return ctx.error(&err);
+        }
+
+        match end {
+            End::Delim => 0,
+            End::Eof => 1,
+            End::Timeout => 128 + libc::SIGALRM,
+        }
+    }
+}
+
+fn parse(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+
+    let mut i = 0;
+
+    while let Some(arg) = args.get(i) {
+        i += 1;
+
+        if arg == "--" {
+            break;
+        }
+
+        if arg.len() < 2 || arg.starts_with('-') == false {
+            i -= 1;
+            break;
+        }
+
+        for (j, flag) in arg.char_indices().skip(1) {
+            if flag == 'r' || flag == 's' {
+                options.is_raw |= flag == 'r';
+                options.is_silent |= flag == 's';
+                continue;
+            }
+
+            if "adnpt".contains(flag) == false {
+                return Err(format!("-{flag}: invalid option"));
+            }
+
+            // the value is the rest of the argument or the next one.
+            let value = match &arg[j + 1..] {
+                "" => {
+                    i += 1;
+
+                    match args.get(i - 1) {
+                        Some(value) => value.clone(),
+                        None => return Err(format!("-{flag}: option requires an argument")),
+                    }
+                }
+                rest => rest.to_owned(),
+            };
+
+            match flag {
+                'a' => options.array = Some(value),
+                'd' => options.delim = Some(value.bytes().next().unwrap_or(0)),
+                'n' => match value.parse::<usize>() {
+                    Ok(count) => options.count = Some(count),
+                    Err(_) => return Err(format!("{value}: invalid number")),
+                },
+                'p' => options.prompt = Some(value),
+                _ => match value.parse::<f64>() {
+                    Ok(seconds) if seconds >= 0.0 => {
+                        options.timeout = Some(Duration::from_secs_f64(seconds))
+                    }
+                    _ => return Err(format!("{value}: invalid timeout specification")),
+                },
+            }
+
+            break;
+        }
+    }
+
+    options.names = args[i..].to_vec();
+
+    Ok(options)
+}
+
+// reads up to delim, which is left out, or count characters. without -r a
+// backslash quotes the next byte and a backslash before a newline joins lines,
+// the offsets of quoted bytes are returned too.
+fn read(ctx: &mut Context, options: &Options, delim: u8) -> io::Result<(Vec<u8>, Vec<usize>, End)> {
+    let mut input = Vec::new();
+
+    let mut quoted = Vec::new();
+
+    let mut chars = 0;
+
+    let mut is_escaped = false;
+
+    let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+
+    loop {
+        if options.count.is_some_and(|count| chars >= count) && std::str::from_utf8(&input).is_ok()
+        {
+            return Ok((input, quoted, End::Delim));
+        }
+
+        if let Some(deadline) = deadline {
+            if wait(ctx.stdin.raw(), deadline)? == false {
+                return Ok((input, quoted, End::Timeout));
+            }
+        }
+
+        let mut byte = [0; 1];
+
+        if ctx.stdin.read(&mut byte)? == 0 {
+            return Ok((input, quoted, End::Eof));
+        }
+
+        let byte = byte[0];
+
+        if is_escaped {
+            is_escaped = false;
+
+            if byte == b'\n' {
+                continue;
+            }
+
+            quoted.push(input.len());
+        } else if byte == b'\\' && options.is_raw == false {
+            is_escaped = true;
+            continue;
+        } else if byte == delim {
+            return Ok((input, quoted, End::Delim));
+        }
+
+        // continuation bytes of utf-8 do not start a character.
+        if byte & 0xc0 != 0x80 {
+            chars += 1;
+        }
+
+        input.push(byte);
+    }
+}
+
+// whether fd has input before the deadline.
+fn wait(fd: i32, deadline: Instant) -> io::Result<bool> {
+    loop {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+
+        let mut poll = libc::pollfd {
+            fd: fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+
+        match unsafe { libc::poll(&mut poll, 1, timeout) } {
+            -1 => {
+                let err = io::Error::last_os_error();
+
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            0 => return Ok(false),
+            _ => return Ok(true),
+        }
+    }
+}
+
+// fields separated by IFS: runs of IFS whitespace, each with at most one other
+// IFS character. characters quoted by a backslash never separate. the last of
+// max fields is the rest of the line.
+fn split(line: &[(char, bool)], ifs: &str, max: usize) -> Vec<String> {
+    let is_ifs = |(ch, is_quoted): &(char, bool)| *is_quoted == false && ifs.contains(*ch);
+
+    let is_space = |item: &(char, bool)| is_ifs(item) && item.0.is_whitespace();
+
+    let text = |chars: &[(char, bool)]| chars.iter().map(|(ch, _)| ch).collect::<String>();
+
+    let mut fields = Vec::new();
+
+    let mut rest = trim_start(line, is_space);
+
+    let end = rest.iter().rposition(|item| is_space(item) == false);
+
+    rest = &rest[..end.map(|end| end + 1).unwrap_or(0)];
+
+    while rest.is_empty() == false {
+        if fields.len() + 1 == max {
+            fields.push(text(rest));
+            break;
+        }
+
+        let i = match rest.iter().position(is_ifs) {
+            Some(i) => i,
+            None => {
+                fields.push(text(rest));
+                break;
+            }
+        };
+
+        fields.push(text(&rest[..i]));
+
+        rest = trim_start(&rest[i..], is_space);
+
+        if rest
+            .first()
+            .is_some_and(|item| is_ifs(item) && is_space(item) == false)
+        {
+            rest = trim_start(&rest[1..], is_space);
+        }
+    }
+
+    fields
+}
+
+fn trim_start<F>(chars: &[(char, bool)], f: F) -> &[(char, bool)]
+where
+    F: Fn(&(char, bool)) -> bool,
+{
+    let start = chars.iter().position(|item| f(item) == false);
+
+    &chars[start.unwrap_or(chars.len())..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::Shell;
+    use crate::testing::TestShell;
+
+    fn fields(line: &str, ifs: &str, max: usize) -> Vec<String> {
+        let chars = line
+            .chars()
+            .map(|ch| (ch, false))
+            .collect::<Vec<(char, bool)>>();
+
+        split(&chars, ifs, max)
+    }
+
+    #[test]
+    fn whitespace_runs_separate_once() {
+        assert_eq!(
+            fields("  a \t b\nc  ", DEFAULT_IFS, usize::MAX),
+            ["a", "b", "c"]
+        );
+        assert_eq!(fields("", DEFAULT_IFS, usize::MAX), Vec::<String>::new());
+    }
+
+    #[test]
+    fn other_ifs_characters_separate_each_time() {
+        assert_eq!(fields("a::b:", ":", usize::MAX), ["a", "", "b"]);
+        assert_eq!(fields("a : b", " :", usize::MAX), ["a", "b"]);
+        assert_eq!(fields(":a", ":", usize::MAX), ["", "a"]);
+    }
+
+    #[test]
+    fn last_field_is_the_rest_of_the_line() {
+        assert_eq!(fields("  a  b  c  ", DEFAULT_IFS, 2), ["a", "b  c"]);
+        assert_eq!(fields("a:b:c", ":", 2), ["a", "b:c"]);
+    }
+
+    #[test]
+    fn backslash_quotes_unless_raw() {
+        let mut shell = TestShell::new();
+
+        assert_eq!(shell.run_input(&["read", "a", "b"], "x\\ y z\n").2, 0);
+        assert_eq!(shell.state().variable("a").as_deref(), Some("x y"));
+        assert_eq!(shell.state().variable("b").as_deref(), Some("z"));
+
+        shell.run_input(&["read", "-r", "a", "b"], "x\\ y z\n");
+
+        assert_eq!(shell.state().variable("a").as_deref(), Some("x\\"));
+        assert_eq!(shell.state().variable("b").as_deref(), Some("y z"));
+    }
+
+    #[test]
+    fn ifs_of_the_shell_splits_into_an_array() {
+        let mut shell = TestShell::new();
+
+        shell.state_mut().assign("IFS", ":".to_owned()).unwrap();
+
+        assert_eq!(shell.run_input(&["read", "-a", "parts"], "a:b c:\n").2, 0);
+        assert_eq!(
+            shell.state().array("parts"),
+            Some(&["a".to_owned(), "b c".to_owned()][..])
+        );
+
+        // no newline before the end of input.
+        assert_eq!(shell.run_input(&["read", "line"], "last").2, 1);
+        assert_eq!(shell.state().variable("line").as_deref(), Some("last"));
+    }
+}
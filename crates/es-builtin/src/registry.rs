@@ -0,0 +1,90 @@
+use crate::builtin::Builtin;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+// builtins by name. the engine looks a command up here before PATH.
+pub struct Registry {
+    builtins: BTreeMap<&'static str, Rc<dyn Builtin>>,
+}
+
+impl Registry {
+    // a registry without any builtin.
+    pub fn new() -> Self {
+        Self {
+            builtins: BTreeMap::new(),
+        }
+    }
+
+    // replaces a builtin of the same name.
+    pub fn register(&mut self, builtin: Rc<dyn Builtin>) {
+        self.builtins.insert(builtin.name(), builtin);
+    }
+
+    pub fn unregister(&mut self, name: &str) -> Option<Rc<dyn Builtin>> {
+        self.builtins.remove(name)
+    }
+
+    // shared, so the builtin can run while the shell owning the registry is
+    // borrowed by it.
+    pub fn get(&self, name: &str) -> Option<Rc<dyn Builtin>> {
+        self.builtins.get(name).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.builtins.contains_key(name)
+    }
+
+    // in name order.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Builtin> {
+        self.builtins.values().map(|builtin| builtin.as_ref())
+    }
+}
+
+impl Default for Registry {
+    // every builtin of the shell.
+    fn default() -> Self {
+        let mut registry = Self::new();
+
+        registry.register(Rc::new(crate::source::Dot));
+        registry.register(Rc::new(crate::test::Bracket));
+        registry.register(Rc::new(crate::abort::Abort));
+        registry.register(Rc::new(crate::alias::Alias));
+        registry.register(Rc::new(crate::argparse::Argparse));
+        registry.register(Rc::new(crate::builtin_command::BuiltinCommand));
+        registry.register(Rc::new(crate::cd::Cd));
+        registry.register(Rc::new(crate::command::Command));
+        registry.register(Rc::new(crate::dirs::Dirs));
+        registry.register(Rc::new(crate::echo::Echo));
+        registry.register(Rc::new(crate::env::Env));
+        registry.register(Rc::new(crate::eval::Eval));
+        registry.register(Rc::new(crate::exec::Exec));
+        registry.register(Rc::new(crate::exit::Exit));
+        registry.register(Rc::new(crate::variable::Export));
+        registry.register(Rc::new(crate::boolean::False));
+        registry.register(Rc::new(crate::getopts::Getopts));
+        registry.register(Rc::new(crate::help::Help));
+        registry.register(Rc::new(crate::kill::Kill));
+        registry.register(Rc::new(crate::variable::Local));
+        registry.register(Rc::new(crate::dirs::Popd));
+        registry.register(Rc::new(crate::printf::Printf));
+        registry.register(Rc::new(crate::dirs::Pushd));
+        registry.register(Rc::new(crate::pwd::Pwd));
+        registry.register(Rc::new(crate::read::Read));
+        registry.register(Rc::new(crate::variable::Readonly));
+        registry.register(Rc::new(crate::set::Set));
+        registry.register(Rc::new(crate::shift::Shift));
+        registry.register(Rc::new(crate::source::Source));
+        registry.register(Rc::new(crate::test::Test));
+        registry.register(Rc::new(crate::trap::Trap));
+        registry.register(Rc::new(crate::boolean::True));
+        registry.register(Rc::new(crate::r#type::Type));
+        registry.register(Rc::new(crate::ulimit::Ulimit));
+        registry.register(Rc::new(crate::umask::Umask));
+        registry.register(Rc::new(crate::alias::Unalias));
+        registry.register(Rc::new(crate::variable::Unset));
+        registry.register(Rc::new(crate::wait::Wait));
+        registry.register(Rc::new(crate::which::Which));
+
+        registry
+    }
+}
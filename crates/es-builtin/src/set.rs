@@ -0,0 +1,134 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::escape;
+use crate::setting::Setting;
+use std::io::Write;
+
+pub struct Set;
+
+impl Builtin for Set {
+    fn name(&self) -> &'static str {
+        "set"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "set [-Ceux] [-o name] [+o name] [--] [arg ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Turn shell options on with - and off with +: -e errexit, -u nounset, \
+         -x xtrace, -C noclobber, -o pipefail or any of them by name. -o alone \
+         prints the options, +o alone prints them as set commands. Arguments \
+         after the options, or after --, become the positional parameters. \
+         Without arguments, print every variable."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-C", "noclobber: do not overwrite files with >"),
+            ("-e", "errexit: exit when a command fails"),
+            ("-u", "nounset: fail on unset variables"),
+            ("-x", "xtrace: print commands before running them"),
+            (
+                "-o name",
+                "turn an option on by name, print the options without one",
+            ),
+            (
+                "+o name",
+                "turn an option off by name, print them as set commands without one",
+            ),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = ctx.args().to_vec();
+
+        if args.is_empty() {
+            let output = ctx
+                .state()
+                .variables()
+                .into_iter()
+                .map(|(name, value)| format!("{name}={}\n", escape::quote(&value)))
+                .collect::<String>();
+
+            return print(ctx, &output);
+        }
+
+        let mut i = 0;
+
+        while i < args.len() {
+            let arg = &args[i];
+
+            if arg == "--" {
+                i += 1;
+                break;
+            }
+
+            let is_on = match arg.chars().next() {
+                Some('-') => true,
+                Some('+') => false,
+                _ => break,
+            };
+
+            if arg.len() < 2 {
+                i += 1;
+                break;
+            }
+
+            for flag in arg[1..].chars() {
+                let setting = match flag {
+                    'o' => match args.get(i + 1) {
+                        Some(name) => {
+                            i += 1;
+
+                            match Setting::from_name(name) {
+                                Some(setting) => setting,
+                                None => return ctx.error(&format!("{name}: invalid option name")),
+                            }
+                        }
+                        None => return print_settings(ctx, is_on),
+                    },
+                    flag => match Setting::from_flag(flag) {
+                        Some(setting) => setting,
+                        None => return ctx.error(&format!("{}{flag}: invalid option", &arg[..1])),
+                    },
+                };
+
+                ctx.state_mut().set(setting, is_on);
+            }
+
+            i += 1;
+        }
+
+        // set -- with nothing after it clears them.
+        if i < args.len() || args.get(i.wrapping_sub(1)).is_some_and(|arg| arg == "--") {
+            ctx.state_mut().set_args(args[i..].to_vec());
+        }
+
+        0
+    }
+}
+
+// -o prints a table, +o the commands that restore the options.
+fn print_settings(ctx: &mut Context, is_table: bool) -> i32 {
+    let state = ctx.state();
+
+    let output = Setting::ALL
+        .iter()
+        .map(|setting| match (is_table, state.is_set(*setting)) {
+            (true, true) => format!("{:<15} on\n", setting.name()),
+            (true, false) => format!("{:<15} off\n", setting.name()),
+            (false, true) => format!("set -o {setting}\n"),
+            (false, false) => format!("set +o {setting}\n"),
+        })
+        .collect::<String>();
+
+    print(ctx, &output)
+}
+
+fn print(ctx: &mut Context, output: &str) -> i32 {
+    match ctx.stdout.write_all(output.as_bytes()) {
+        Ok(_) => 0,
+        Err(err) => ctx.error(&format!("write error: {err}")),
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+// shell options turned on and off by set.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Setting {
+    // exit when a command fails.
+    Errexit,
+    // an unset variable is an error when expanded.
+    Nounset,
+    // print commands before running them.
+    Xtrace,
+    // a pipeline fails when any of its commands fails.
+    Pipefail,
+    // > does not overwrite an existing file.
+    Noclobber,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 5] = [
+        Setting::Errexit,
+        Setting::Noclobber,
+        Setting::Nounset,
+        Setting::Pipefail,
+        Setting::Xtrace,
+    ];
+
+    // the name for set -o.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Setting::Errexit => "errexit",
+            Setting::Nounset => "nounset",
+            Setting::Xtrace => "xtrace",
+            Setting::Pipefail => "pipefail",
+            Setting::Noclobber => "noclobber",
+        }
+    }
+
+    // the single letter flag, pipefail has none.
+    pub fn flag(&self) -> Option<char> {
+        match self {
+            Setting::Errexit => Some('e'),
+            Setting::Nounset => Some('u'),
+            Setting::Xtrace => Some('x'),
+            Setting::Pipefail => None,
+            Setting::Noclobber => Some('C'),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Setting> {
+        Setting::ALL
+            .into_iter()
+            .find(|setting| setting.name() == name)
+    }
+
+    pub fn from_flag(flag: char) -> Option<Setting> {
+        Setting::ALL
+            .into_iter()
+            .find(|setting| setting.flag() == Some(flag))
+    }
+}
+
+impl Display for Setting {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::io::Fd;
+use crate::registry::Registry;
+use crate::state::State;
+
+// what a command name may resolve to, for builtins that look names up or run
+// other commands.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Lookup {
+    // functions, then builtins, then PATH.
+    All,
+    // builtins, then PATH. used by `command`.
+    NoFunctions,
+    // builtins only. used by `builtin`.
+    BuiltinsOnly,
+}
+
+// the shell running a builtin, implemented by the engine.
+pub trait Shell {
+    fn state(&self) -> &State;
+
+    fn state_mut(&mut self) -> &mut State;
+
+    fn registry(&self) -> &Registry;
+
+    // runs argv as a command with the given stdin, stdout and stderr and
+    // returns its exit status.
+    fn execute(&mut self, argv: &[String], io: [Fd; 3], lookup: Lookup) -> i32;
+
+    // the background jobs still running, oldest first, with the number each got
+    // when it started and its pid. a job keeps its number until it ends.
+    fn jobs(&self) -> Vec<(usize, libc::pid_t)>;
+
+    // %n is the job numbered n, %%, %+ and a lone % are the latest job and %-
+    // the one before it. spec is what follows the %.
+    fn job(&self, spec: &str) -> Option<libc::pid_t> {
+        let jobs = self.jobs();
+
+        let job = match spec {
+            "" | "%" | "+" => jobs.last(),
+            "-" => jobs.iter().rev().nth(1),
+            n => {
+                let n = n.parse::<usize>().ok()?;
+
+                jobs.iter().find(|(number, _)| *number == n)
+            }
+        };
+
+        job.map(|(_, pid)| *pid)
+    }
+
+    // waits for a background job and returns its status, None when pid is not
+    // one. without a pid it waits for all of them and returns 0.
+    fn wait(&mut self, pid: Option<libc::pid_t>) -> Option<i32>;
+
+    // parses source and runs it in the current scope, reporting errors under
+    // name. args, when given, are the positional parameters while it runs.
+    fn source(&mut self, name: &str, source: &str, args: Option<Vec<String>>, io: [Fd; 3]) -> i32;
+}
@@ -0,0 +1,43 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+
+pub struct Shift;
+
+impl Builtin for Shift {
+    fn name(&self) -> &'static str {
+        "shift"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "shift [n]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Drop the first n positional parameters, 1 by default, so that $n+1 \
+         becomes $1. Fails and leaves them as they are when there are fewer \
+         than n."
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let n = match ctx.args() {
+            [] => 1,
+            [n] => match n.parse::<usize>() {
+                Ok(n) => n,
+                Err(_) => return ctx.error(&format!("{n}: numeric argument required")),
+            },
+            _ => return ctx.error("too many arguments"),
+        };
+
+        let args = ctx.state().args();
+
+        if n > args.len() {
+            return ctx.error(&format!("{n}: shift count out of range"));
+        }
+
+        let args = args[n..].to_vec();
+
+        ctx.state_mut().set_args(args);
+
+        0
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+// signals by name, without the SIG prefix, in number order on linux.
+pub const SIGNALS: [(&str, libc::c_int); 30] = [
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("ILL", libc::SIGILL),
+    ("TRAP", libc::SIGTRAP),
+    ("ABRT", libc::SIGABRT),
+    ("BUS", libc::SIGBUS),
+    ("FPE", libc::SIGFPE),
+    ("KILL", libc::SIGKILL),
+    ("USR1", libc::SIGUSR1),
+    ("SEGV", libc::SIGSEGV),
+    ("USR2", libc::SIGUSR2),
+    ("PIPE", libc::SIGPIPE),
+    ("ALRM", libc::SIGALRM),
+    ("TERM", libc::SIGTERM),
+    ("CHLD", libc::SIGCHLD),
+    ("CONT", libc::SIGCONT),
+    ("STOP", libc::SIGSTOP),
+    ("TSTP", libc::SIGTSTP),
+    ("TTIN", libc::SIGTTIN),
+    ("TTOU", libc::SIGTTOU),
+    ("URG", libc::SIGURG),
+    ("XCPU", libc::SIGXCPU),
+    ("XFSZ", libc::SIGXFSZ),
+    ("VTALRM", libc::SIGVTALRM),
+    ("PROF", libc::SIGPROF),
+    ("WINCH", libc::SIGWINCH),
+    ("IO", libc::SIGIO),
+    ("SYS", libc::SIGSYS),
+    ("EXIT", 0),
+];
+
+// caught by the shell itself even without a trap: INT stops the foreground job,
+// CHLD reaps background jobs and HUP is passed on to them.
+pub const SHELL: [libc::c_int; 3] = [libc::SIGINT, libc::SIGCHLD, libc::SIGHUP];
+
+// signals that arrived and were not handled yet, one bit each.
+static PENDING: AtomicU64 = AtomicU64::new(0);
+
+// signals caught by handler, put back to the default in forks.
+static CAUGHT: AtomicU64 = AtomicU64::new(0);
+
+// the number of a signal given as INT, SIGINT, int or 2. EXIT is 0.
+pub fn number(name: &str) -> Option<libc::c_int> {
+    if let Ok(number) = name.parse::<libc::c_int>() {
+        return SIGNALS
+            .iter()
+            .find(|(_, other)| *other == number)
+            .map(|(_, number)| *number);
+    }
+
+    let name = name.to_ascii_uppercase();
+
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+
+    SIGNALS
+        .iter()
+        .find(|(other, _)| *other == name)
+        .map(|(_, number)| *number)
+}
+
+pub fn name(number: libc::c_int) -> Option<&'static str> {
+    SIGNALS
+        .iter()
+        .find(|(_, other)| *other == number)
+        .map(|(name, _)| *name)
+}
+
+// records the signal for take instead of its default action.
+pub fn catch(signal: libc::c_int) {
+    CAUGHT.fetch_or(1 << signal, Ordering::SeqCst);
+
+    set_action(
+        signal,
+        handler as extern "C" fn(libc::c_int) as libc::sighandler_t,
+    );
+}
+
+pub fn ignore(signal: libc::c_int) {
+    CAUGHT.fetch_and(!(1 << signal), Ordering::SeqCst);
+
+    set_action(signal, libc::SIG_IGN);
+}
+
+pub fn restore(signal: libc::c_int) {
+    CAUGHT.fetch_and(!(1 << signal), Ordering::SeqCst);
+
+    set_action(signal, libc::SIG_DFL);
+}
+
+// puts caught signals back to their default, for a fork that goes on to run a
+// command. ignored ones stay ignored.
+pub fn reset() {
+    let caught = CAUGHT.swap(0, Ordering::SeqCst);
+
+    for signal in 1..64 {
+        if caught & (1 << signal) != 0 {
+            set_action(signal, libc::SIG_DFL);
+        }
+    }
+
+    PENDING.store(0, Ordering::SeqCst);
+}
+
+// whether the signal arrived since the last call, clearing it.
+pub fn take(signal: libc::c_int) -> bool {
+    PENDING.fetch_and(!(1 << signal), Ordering::SeqCst) & (1 << signal) != 0
+}
+
+// every signal that arrived since the last call, in number order.
+pub fn take_all() -> Vec<libc::c_int> {
+    let pending = PENDING.swap(0, Ordering::SeqCst);
+
+    (1..64)
+        .filter(|signal| pending & (1 << signal) != 0)
+        .collect()
+}
+
+// only async-signal-safe work in here.
+extern "C" fn handler(signal: libc::c_int) {
+    PENDING.fetch_or(1 << signal, Ordering::SeqCst);
+}
+
+// reads and waits are restarted, except on SIGINT and SIGHUP which should get
+// the shell out of a blocking read.
+fn set_action(signal: libc::c_int, action: libc::sighandler_t) {
+    unsafe {
+        let mut sigaction = std::mem::zeroed::<libc::sigaction>();
+
+        sigaction.sa_sigaction = action;
+
+        if signal != libc::SIGINT && signal != libc::SIGHUP {
+            sigaction.sa_flags = libc::SA_RESTART;
+        }
+
+        libc::sigemptyset(&mut sigaction.sa_mask);
+
+        libc::sigaction(signal, &sigaction, std::ptr::null_mut());
+    }
+}
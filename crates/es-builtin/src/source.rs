@@ -0,0 +1,80 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+pub struct Source;
+
+pub struct Dot;
+
+impl Builtin for Source {
+    fn name(&self) -> &'static str {
+        "source"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "source file [arg ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run the commands of file in the current shell. A file name without a \
+         slash is searched in PATH, then in the working directory. The args are \
+         the positional parameters while it runs."
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        source(ctx)
+    }
+}
+
+impl Builtin for Dot {
+    fn name(&self) -> &'static str {
+        "."
+    }
+
+    fn synopsis(&self) -> &'static str {
+        ". file [arg ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run the commands of file in the current shell, like source."
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        source(ctx)
+    }
+}
+
+fn source(ctx: &mut Context) -> i32 {
+    let (name, args) = match ctx.args() {
+        [] => return ctx.error("filename argument required"),
+        [name, args @ ..] => (name.clone(), args.to_vec()),
+    };
+
+    let path = match name.contains('/') {
+        true => PathBuf::from(&name),
+        false => {
+            let path = ctx.state().variable("PATH").unwrap_or_default();
+
+            env::split_paths(&path)
+                .map(|dir| dir.join(&name))
+                .find(|file| file.is_file())
+                .unwrap_or(PathBuf::from(&name))
+        }
+    };
+
+    let source = match fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(err) => return ctx.error(&format!("{name}: {err}")),
+    };
+
+    let args = match args.is_empty() {
+        true => None,
+        false => Some(args),
+    };
+
+    let io = ctx.io();
+
+    ctx.shell.source(&path.to_string_lossy(), &source, args, io)
+}
@@ -0,0 +1,372 @@
+use crate::setting::Setting;
+use es_ast::Def;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::env;
+use std::process;
+
+// everything a script can change about the shell it runs in.
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    variables: HashMap<String, String>,
+    // indexed variables, set by read -a. $name is all of the elements joined
+    // by spaces, ${name[i]} one of them.
+    arrays: HashMap<String, Vec<String>>,
+    functions: HashMap<String, Def>,
+    // alias names and their text, the parser expands them.
+    aliases: BTreeMap<String, String>,
+    // $0 and the positional parameters $1, $2 ...
+    name: String,
+    args: Vec<String>,
+    // exit status of the last command, $?.
+    status: i32,
+    // set by the exit builtin, the shell stops once it sees it.
+    exit: Option<i32>,
+    // the directory stack of pushd and popd, most recent first. the working
+    // directory itself is not in it.
+    dirs: Vec<String>,
+    // names export marked before they had a value. a variable that is in the
+    // environment is exported anyway.
+    exported: HashSet<String>,
+    readonly: HashSet<String>,
+    // the variables a function call made local, with what to put back once it
+    // returns. innermost call last.
+    scopes: Vec<HashMap<String, Saved>>,
+    settings: BTreeSet<Setting>,
+    // trap actions by condition: EXIT, ERR or a signal name without SIG.
+    traps: BTreeMap<String, String>,
+    // the pid of the last command started in the background, $!.
+    last_pid: Option<u32>,
+    // where getopts is within a group of flags such as -abc: the OPTIND it
+    // left and the index of the next flag in that argument.
+    getopts: (usize, usize),
+}
+
+// a variable as it was before a function made it local.
+#[derive(Debug, Clone)]
+struct Saved {
+    value: Option<String>,
+    env: Option<String>,
+}
+
+impl State {
+    pub fn new(name: String, args: Vec<String>) -> Self {
+        Self {
+            name: name,
+            args: args,
+            ..Default::default()
+        }
+    }
+
+    // special parameters first, then shell variables, then the environment.
+    pub fn variable(&self, name: &str) -> Option<String> {
+        match name {
+            "?" => return Some(self.status.to_string()),
+            "#" => return Some(self.args.len().to_string()),
+            "@" | "*" => return Some(self.args.join(" ")),
+            "$" => return Some(process::id().to_string()),
+            "0" => return Some(self.name.clone()),
+            "!" => return self.last_pid.map(|pid| pid.to_string()),
+            "-" => {
+                return Some(
+                    self.settings
+                        .iter()
+                        .filter_map(|setting| setting.flag())
+                        .collect(),
+                )
+            }
+            _ => {}
+        }
+
+        if let Ok(n) = name.parse::<usize>() {
+            return self.args.get(n.wrapping_sub(1)).cloned();
+        }
+
+        if let Some((name, index)) = name.strip_suffix(']').and_then(|name| name.split_once('[')) {
+            let array = self.arrays.get(name)?;
+
+            return match index {
+                "@" | "*" => Some(array.join(" ")),
+                index => array.get(index.parse::<usize>().ok()?).cloned(),
+            };
+        }
+
+        if let Some(array) = self.arrays.get(name) {
+            return Some(array.join(" "));
+        }
+
+        match self.variables.get(name) {
+            Some(value) => Some(value.clone()),
+            None => env::var(name).ok(),
+        }
+    }
+
+    // an exported variable is also set in the environment, for the programs the
+    // shell starts.
+    pub fn set_variable(&mut self, name: &str, value: String) {
+        if self.is_exported(name) {
+            env::set_var(name, &value);
+        }
+
+        self.arrays.remove(name);
+
+        self.variables.insert(name.to_owned(), value);
+    }
+
+    // set_variable unless the variable is readonly.
+    pub fn assign(&mut self, name: &str, value: String) -> Result<(), String> {
+        if self.is_readonly(name) {
+            return Err(format!("{name}: readonly variable"));
+        }
+
+        self.set_variable(name, value);
+
+        Ok(())
+    }
+
+    // removes the variable from the shell and the environment.
+    pub fn unset_variable(&mut self, name: &str) -> Option<String> {
+        let env = env::var(name).ok();
+
+        env::remove_var(name);
+
+        self.exported.remove(name);
+
+        let array = self.arrays.remove(name).map(|array| array.join(" "));
+
+        self.variables.remove(name).or(array).or(env)
+    }
+
+    pub fn array(&self, name: &str) -> Option<&[String]> {
+        self.arrays.get(name).map(|array| array.as_slice())
+    }
+
+    // replaces the variable name by an array, unless it is readonly.
+    pub fn assign_array(&mut self, name: &str, values: Vec<String>) -> Result<(), String> {
+        if self.is_readonly(name) {
+            return Err(format!("{name}: readonly variable"));
+        }
+
+        self.variables.remove(name);
+
+        self.arrays.insert(name.to_owned(), values);
+
+        Ok(())
+    }
+
+    // every variable with a value, the environment included, by name.
+    pub fn variables(&self) -> BTreeMap<String, String> {
+        let mut variables = env::vars().collect::<BTreeMap<String, String>>();
+
+        for (name, value) in &self.variables {
+            variables.insert(name.clone(), value.clone());
+        }
+
+        variables
+    }
+
+    pub fn is_exported(&self, name: &str) -> bool {
+        self.exported.contains(name) || env::var_os(name).is_some()
+    }
+
+    // puts the variable in the environment now, or once it gets a value.
+    pub fn export(&mut self, name: &str) {
+        if let Some(value) = self.variables.get(name) {
+            env::set_var(name, value);
+        }
+
+        self.exported.insert(name.to_owned());
+    }
+
+    // exported names without a value yet, by name.
+    pub fn exported(&self) -> BTreeSet<String> {
+        self.exported
+            .iter()
+            .filter(|name| env::var_os(name).is_none())
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_readonly(&self, name: &str) -> bool {
+        self.readonly.contains(name)
+    }
+
+    pub fn set_readonly(&mut self, name: &str) {
+        self.readonly.insert(name.to_owned());
+    }
+
+    // readonly names, by name.
+    pub fn readonly(&self) -> BTreeSet<String> {
+        self.readonly.iter().cloned().collect()
+    }
+
+    // starts the scope of a function call.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    // ends the scope of a function call, the variables it made local get their
+    // old values back.
+    pub fn pop_scope(&mut self) {
+        let scope = match self.scopes.pop() {
+            Some(scope) => scope,
+            None => return,
+        };
+
+        for (name, saved) in scope {
+            match saved.value {
+                Some(value) => self.variables.insert(name.clone(), value),
+                None => self.variables.remove(&name),
+            };
+
+            match saved.env {
+                Some(value) => env::set_var(&name, value),
+                None => env::remove_var(&name),
+            }
+        }
+    }
+
+    // makes a variable local to the current function call. false outside of a
+    // function.
+    pub fn local(&mut self, name: &str) -> bool {
+        let saved = Saved {
+            value: self.variables.get(name).cloned(),
+            env: env::var(name).ok(),
+        };
+
+        match self.scopes.last_mut() {
+            Some(scope) => {
+                scope.entry(name.to_owned()).or_insert(saved);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn function(&self, name: &str) -> Option<&Def> {
+        self.functions.get(name)
+    }
+
+    pub fn set_function(&mut self, def: Def) {
+        self.functions.insert(def.name().to_owned(), def);
+    }
+
+    pub fn unset_function(&mut self, name: &str) -> Option<Def> {
+        self.functions.remove(name)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    // replaces the positional parameters, returning the old ones.
+    pub fn set_args(&mut self, args: Vec<String>) -> Vec<String> {
+        std::mem::replace(&mut self.args, args)
+    }
+
+    pub fn status(&self) -> i32 {
+        self.status
+    }
+
+    pub fn set_status(&mut self, status: i32) {
+        self.status = status;
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit
+    }
+
+    pub fn exit(&mut self, code: i32) {
+        self.exit = Some(code);
+    }
+
+    // clears the request to exit, so an EXIT trap can run to its end.
+    pub fn take_exit(&mut self) -> Option<i32> {
+        self.exit.take()
+    }
+
+    pub fn dirs(&self) -> &[String] {
+        &self.dirs
+    }
+
+    pub fn dirs_mut(&mut self) -> &mut Vec<String> {
+        &mut self.dirs
+    }
+
+    pub fn is_set(&self, setting: Setting) -> bool {
+        self.settings.contains(&setting)
+    }
+
+    pub fn set(&mut self, setting: Setting, is_on: bool) {
+        match is_on {
+            true => self.settings.insert(setting),
+            false => self.settings.remove(&setting),
+        };
+    }
+
+    pub fn alias(&self, name: &str) -> Option<&str> {
+        self.aliases.get(name).map(|text| text.as_str())
+    }
+
+    pub fn set_alias(&mut self, name: &str, text: String) {
+        self.aliases.insert(name.to_owned(), text);
+    }
+
+    // whether there was such an alias.
+    pub fn unalias(&mut self, name: &str) -> bool {
+        self.aliases.remove(name).is_some()
+    }
+
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        &self.aliases
+    }
+
+    pub fn clear_aliases(&mut self) {
+        self.aliases.clear();
+    }
+
+    pub fn trap(&self, condition: &str) -> Option<&str> {
+        self.traps.get(condition).map(|action| action.as_str())
+    }
+
+    // None removes the trap.
+    pub fn set_trap(&mut self, condition: &str, action: Option<String>) {
+        match action {
+            Some(action) => self.traps.insert(condition.to_owned(), action),
+            None => self.traps.remove(condition),
+        };
+    }
+
+    pub fn traps(&self) -> &BTreeMap<String, String> {
+        &self.traps
+    }
+
+    pub fn set_last_pid(&mut self, pid: u32) {
+        self.last_pid = Some(pid);
+    }
+
+    pub fn getopts(&self) -> (usize, usize) {
+        self.getopts
+    }
+
+    pub fn set_getopts(&mut self, optind: usize, index: usize) {
+        self.getopts = (optind, index);
+    }
+}
+
+// a name a variable can have: a letter or `_`, then letters, digits and `_`.
+pub fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    let is_start = chars
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_');
+
+    is_start && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
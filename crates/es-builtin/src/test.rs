@@ -0,0 +1,415 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::compare;
+use es_ast::ComparisonKind;
+use std::ffi::CString;
+use std::fs;
+use std::fs::Metadata;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::fs::MetadataExt;
+
+pub struct Test;
+
+pub struct Bracket;
+
+impl Builtin for Test {
+    fn name(&self) -> &'static str {
+        "test"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "test [expression]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Evaluate a conditional expression, the status is 0 when it is true, 1 \
+         when it is false and 2 on errors. Files are checked with -e -f -d -h -L \
+         -b -c -p -S -r -w -x -s -u -g -k -O -G, -nt -ot and -ef, terminals with \
+         -t fd, strings with -n -z = == != < >, integers with -eq -ne -lt -le -gt \
+         -ge. ! negates, -a and -o join and parentheses group expressions."
+    }
+
+    fn has_help(&self) -> bool {
+        false
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = ctx.args().to_vec();
+
+        status(ctx, &args)
+    }
+}
+
+impl Builtin for Bracket {
+    fn name(&self) -> &'static str {
+        "["
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "[ [expression] ]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Evaluate a conditional expression like test, the last argument must be ]."
+    }
+
+    fn has_help(&self) -> bool {
+        false
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = match ctx.args().split_last() {
+            Some((last, args)) if last == "]" => args.to_vec(),
+            _ => {
+                ctx.error("missing `]'");
+                return 2;
+            }
+        };
+
+        status(ctx, &args)
+    }
+}
+
+fn status(ctx: &mut Context, args: &[String]) -> i32 {
+    match test(args) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(err) => {
+            ctx.error(&err);
+            2
+        }
+    }
+}
+
+// the POSIX rules for up to four arguments, which keep `[ "$a" = ! ]` and the
+// like unambiguous, then the full grammar.
+fn test(args: &[String]) -> Result<bool, String> {
+    let args = args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>();
+
+    match args.as_slice() {
+        [] => Ok(false),
+        [arg] => Ok(arg.is_empty() == false),
+        ["!", arg] => Ok(arg.is_empty()),
+        [op, arg] if is_unary(op) => unary(op, arg),
+        [op, _] => Err(format!("{op}: unary operator expected")),
+        [left, op, right] if is_binary(op) => binary(left, op, right),
+        ["!", _, _] => test(&strings(&args[1..])).map(|value| value == false),
+        ["(", arg, ")"] => Ok(arg.is_empty() == false),
+        ["!", _, _, _] => test(&strings(&args[1..])).map(|value| value == false),
+        ["(", _, _, ")"] => test(&strings(&args[1..3])),
+        _ => {
+            let mut parser = Parser {
+                args: &args,
+                position: 0,
+            };
+
+            let value = parser.or()?;
+
+            match parser.peek() {
+                None => Ok(value),
+                Some(arg) => Err(format!("{arg}: unexpected argument")),
+            }
+        }
+    }
+}
+
+fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+// or := and (-o and)*, and := not (-a not)*, not := ! not | primary,
+// primary := ( or ) | unary arg | arg binary arg | arg
+struct Parser<'a> {
+    args: &'a [&'a str],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.args.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Result<&'a str, String> {
+        let arg = self.args.get(self.position).copied();
+
+        self.position += 1;
+
+        arg.ok_or("argument expected".to_owned())
+    }
+
+    fn or(&mut self) -> Result<bool, String> {
+        let mut value = self.and()?;
+
+        while self.peek() == Some("-o") {
+            self.position += 1;
+
+            // both sides are parsed, so errors on the right are reported.
+            let right = self.and()?;
+
+            value = value || right;
+        }
+
+        Ok(value)
+    }
+
+    fn and(&mut self) -> Result<bool, String> {
+        let mut value = self.not()?;
+
+        while self.peek() == Some("-a") {
+            self.position += 1;
+
+            let right = self.not()?;
+
+            value = value && right;
+        }
+
+        Ok(value)
+    }
+
+    fn not(&mut self) -> Result<bool, String> {
+        match self.peek() {
+            Some("!") => {
+                self.position += 1;
+
+                self.not().map(|value| value == false)
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<bool, String> {
+        let arg = self.next()?;
+
+        if arg == "(" {
+            let value = self.or()?;
+
+            return match self.next() {
+                Ok(")") => Ok(value),
+                _ => Err("missing `)'".to_owned()),
+            };
+        }
+
+        // an operator with nothing after it is taken as a string.
+        if is_unary(arg) && self.peek().is_some() {
+            let operand = self.next()?;
+
+            return unary(arg, operand);
+        }
+
+        match self.peek() {
+            Some(op) if is_binary(op) => {
+                self.position += 1;
+
+                let right = self.next()?;
+
+                binary(arg, op, right)
+            }
+            _ => Ok(arg.is_empty() == false),
+        }
+    }
+}
+
+fn is_unary(op: &str) -> bool {
+    matches!(
+        op,
+        "-b" | "-c"
+            | "-d"
+            | "-e"
+            | "-f"
+            | "-g"
+            | "-G"
+            | "-h"
+            | "-k"
+            | "-L"
+            | "-n"
+            | "-O"
+            | "-p"
+            | "-r"
+            | "-s"
+            | "-S"
+            | "-t"
+            | "-u"
+            | "-w"
+            | "-x"
+            | "-z"
+    )
+}
+
+fn is_binary(op: &str) -> bool {
+    integer_op(op).is_some() || matches!(op, "=" | "==" | "!=" | "<" | ">" | "-nt" | "-ot" | "-ef")
+}
+
+fn unary(op: &str, arg: &str) -> Result<bool, String> {
+    match op {
+        "-n" => return Ok(arg.is_empty() == false),
+        "-z" => return Ok(arg.is_empty()),
+        "-t" => {
+            return match compare::integer(arg) {
+                Some(fd) => Ok(unsafe { libc::isatty(fd as libc::c_int) } == 1),
+                None => Err(format!("{arg}: integer expression expected")),
+            }
+        }
+        "-r" => return Ok(access(arg, libc::R_OK)),
+        "-w" => return Ok(access(arg, libc::W_OK)),
+        "-x" => return Ok(access(arg, libc::X_OK)),
+        _ => {}
+    }
+
+    // -h and -L look at the link itself, everything else follows it.
+    let metadata = match op {
+        "-h" | "-L" => fs::symlink_metadata(arg),
+        _ => fs::metadata(arg),
+    };
+
+    let metadata = match metadata {
+        Ok(metadata) => metadata,
+        Err(_) => return Ok(false),
+    };
+
+    let file_type = metadata.file_type();
+
+    Ok(match op {
+        "-e" => true,
+        "-f" => file_type.is_file(),
+        "-d" => file_type.is_dir(),
+        "-h" | "-L" => file_type.is_symlink(),
+        "-b" => file_type.is_block_device(),
+        "-c" => file_type.is_char_device(),
+        "-p" => file_type.is_fifo(),
+        "-S" => file_type.is_socket(),
+        "-s" => metadata.len() > 0,
+        "-u" => metadata.mode() & 0o4000 != 0,
+        "-g" => metadata.mode() & 0o2000 != 0,
+        "-k" => metadata.mode() & 0o1000 != 0,
+        "-O" => metadata.uid() == unsafe { libc::geteuid() },
+        "-G" => metadata.gid() == unsafe { libc::getegid() },
+        _ => false,
+    })
+}
+
+fn binary(left: &str, op: &str, right: &str) -> Result<bool, String> {
+    if let Some((kind, is_negated)) = integer_op(op) {
+        let integer =
+            |arg: &str| compare::integer(arg).ok_or(format!("{arg}: integer expression expected"));
+
+        let ordering = integer(left)?.cmp(&integer(right)?);
+
+        return Ok(compare::is_match(kind, ordering) != is_negated);
+    }
+
+    match op {
+        "=" | "==" => Ok(left == right),
+        "!=" => Ok(left != right),
+        "<" => Ok(left < right),
+        ">" => Ok(left > right),
+        "-nt" | "-ot" | "-ef" => Ok(files(left, op, right)),
+        _ => Err(format!("{op}: binary operator expected")),
+    }
+}
+
+// -le is not -gt and -ge not -lt.
+fn integer_op(op: &str) -> Option<(ComparisonKind, bool)> {
+    match op {
+        "-eq" => Some((ComparisonKind::Equal, false)),
+        "-ne" => Some((ComparisonKind::NotEqual, false)),
+        "-lt" => Some((ComparisonKind::Lt, false)),
+        "-gt" => Some((ComparisonKind::Gt, false)),
+        "-le" => Some((ComparisonKind::Gt, true)),
+        "-ge" => Some((ComparisonKind::Lt, true)),
+        _ => None,
+    }
+}
+
+// a missing file is older than any file that exists.
+fn files(left: &str, op: &str, right: &str) -> bool {
+    let modified = |metadata: &Metadata| (metadata.mtime(), metadata.mtime_nsec());
+
+    match (op, fs::metadata(left), fs::metadata(right)) {
+        ("-ef", Ok(left), Ok(right)) => left.dev() == right.dev() && left.ino() == right.ino(),
+        ("-nt", Ok(left), Ok(right)) => modified(&left) > modified(&right),
+        ("-nt", Ok(_), Err(_)) => true,
+        ("-ot", Ok(left), Ok(right)) => modified(&left) < modified(&right),
+        ("-ot", Err(_), Ok(_)) => true,
+        _ => false,
+    }
+}
+
+// whether the process may read, write or execute path.
+fn access(path: &str, mode: libc::c_int) -> bool {
+    match CString::new(path) {
+        Ok(path) => (unsafe { libc::access(path.as_ptr(), mode) }) == 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(args: &[&str]) -> Result<bool, String> {
+        test(&strings(args))
+    }
+
+    #[test]
+    fn zero_and_one_argument() {
+        assert_eq!(eval(&[]), Ok(false));
+        assert_eq!(eval(&[""]), Ok(false));
+
+        // a lone operator is a non-empty string.
+        for arg in ["x", "-n", "-z", "!", "(", "-a"] {
+            assert_eq!(eval(&[arg]), Ok(true));
+        }
+    }
+
+    #[test]
+    fn two_arguments() {
+        assert_eq!(eval(&["!", ""]), Ok(true));
+        assert_eq!(eval(&["!", "x"]), Ok(false));
+        assert_eq!(eval(&["-n", ""]), Ok(false));
+        assert_eq!(eval(&["-z", ""]), Ok(true));
+        assert_eq!(eval(&["-e", "/nonexistent/file"]), Ok(false));
+        assert_eq!(eval(&["-d", "/"]), Ok(true));
+        assert_eq!(
+            eval(&["x", "y"]),
+            Err("x: unary operator expected".to_owned())
+        );
+    }
+
+    #[test]
+    fn three_arguments() {
+        // a binary operator in the middle wins over ! and ( ).
+        assert_eq!(eval(&["!", "=", "!"]), Ok(true));
+        assert_eq!(eval(&["(", "=", ")"]), Ok(false));
+        assert_eq!(eval(&["-n", "!=", "-n"]), Ok(false));
+        assert_eq!(eval(&["!", "-z", ""]), Ok(false));
+        assert_eq!(eval(&["(", "x", ")"]), Ok(true));
+        assert_eq!(eval(&["(", "", ")"]), Ok(false));
+        assert_eq!(eval(&["1", "-lt", "2"]), Ok(true));
+        assert_eq!(eval(&["010", "-eq", "10"]), Ok(true));
+        assert_eq!(eval(&["b", "<", "a"]), Ok(false));
+        assert_eq!(
+            eval(&["1", "-lt", "x"]),
+            Err("x: integer expression expected".to_owned())
+        );
+    }
+
+    #[test]
+    fn four_arguments() {
+        assert_eq!(eval(&["!", "a", "=", "b"]), Ok(true));
+        assert_eq!(eval(&["(", "-z", "x", ")"]), Ok(false));
+        assert_eq!(eval(&["!", "(", "", ")"]), Ok(true));
+    }
+
+    #[test]
+    fn more_arguments_use_the_grammar() {
+        // -a binds tighter than -o.
+        assert_eq!(eval(&["x", "-o", "", "-a", ""]), Ok(true));
+        assert_eq!(eval(&["", "-a", "x", "-o", "y"]), Ok(true));
+        assert_eq!(eval(&["(", "", "-o", "x", ")", "-a", ""]), Ok(false));
+        assert_eq!(eval(&["!", "!", "a", "=", "a"]), Ok(true));
+        assert_eq!(
+            eval(&["a", "=", "a", "b", "c"]),
+            Err("b: unexpected argument".to_owned())
+        );
+    }
+}
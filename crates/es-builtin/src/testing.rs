@@ -0,0 +1,126 @@
+use crate::builtin::Context;
+use crate::io::Fd;
+use crate::registry::Registry;
+use crate::shell::Lookup;
+use crate::shell::Shell;
+use crate::state::State;
+use std::fs::File;
+use std::io::Read;
+use std::io::Write;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+
+// a shell without an engine, for running builtins in tests. commands run only
+// when they are builtins.
+pub struct TestShell {
+    state: State,
+    registry: Registry,
+}
+
+impl TestShell {
+    pub fn new() -> Self {
+        Self {
+            state: State::new("eash".to_owned(), Vec::new()),
+            registry: Registry::default(),
+        }
+    }
+
+    // runs argv and returns what it wrote to stdout and stderr, and its status.
+    pub fn run(&mut self, argv: &[&str]) -> (String, String, i32) {
+        self.run_input(argv, "")
+    }
+
+    // run with input on stdin.
+    pub fn run_input(&mut self, argv: &[&str], input: &str) -> (String, String, i32) {
+        let argv = argv
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<String>>();
+
+        let (stdin, stdin_writer) = pipe();
+
+        File::from(stdin_writer)
+            .write_all(input.as_bytes())
+            .unwrap();
+
+        let (mut stdout_reader, stdout) = pipe();
+
+        let (mut stderr_reader, stderr) = pipe();
+
+        let io = [
+            Fd::new(stdin.as_raw_fd()),
+            Fd::new(stdout.as_raw_fd()),
+            Fd::new(stderr.as_raw_fd()),
+        ];
+
+        let status = self.execute(&argv, io, Lookup::All);
+
+        drop(stdout);
+
+        drop(stderr);
+
+        let mut out = String::new();
+
+        stdout_reader.read_to_string(&mut out).unwrap();
+
+        let mut err = String::new();
+
+        stderr_reader.read_to_string(&mut err).unwrap();
+
+        (out, err, status)
+    }
+}
+
+impl Shell for TestShell {
+    fn state(&self) -> &State {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    fn execute(&mut self, argv: &[String], io: [Fd; 3], _: Lookup) -> i32 {
+        let builtin = match argv.first().and_then(|name| self.registry.get(name)) {
+            Some(builtin) => builtin,
+            None => return 127,
+        };
+
+        let mut ctx = Context {
+            argv: argv,
+            stdin: io[0],
+            stdout: io[1],
+            stderr: io[2],
+            shell: self,
+        };
+
+        builtin.run(&mut ctx)
+    }
+
+    fn jobs(&self) -> Vec<(usize, libc::pid_t)> {
+        Vec::new()
+    }
+
+    fn wait(&mut self, _: Option<libc::pid_t>) -> Option<i32> {
+        None
+    }
+
+    fn source(&mut self, _: &str, _: &str, _: Option<Vec<String>>, _: [Fd; 3]) -> i32 {
+        2
+    }
+}
+
+// the read end and the write end of a pipe. small outputs fit in it, so a
+// builtin can write everything before the test reads.
+fn pipe() -> (File, OwnedFd) {
+    let mut fds = [0; 2];
+
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+
+    unsafe { (File::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) }
+}
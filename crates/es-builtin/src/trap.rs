@@ -0,0 +1,107 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::escape;
+use crate::signal;
+
+pub struct Trap;
+
+impl Builtin for Trap {
+    fn name(&self) -> &'static str {
+        "trap"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "trap [-lp] [[action] condition ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run action when one of the conditions happens: a signal by name or \
+         number, EXIT when the shell exits or ERR when a command fails. An empty \
+         action ignores the signals, - or no action puts them back to their \
+         default. Without arguments or with -p, print the traps that are set, -l \
+         lists the signal names."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-l", "list the signal names"),
+            ("-p", "print the traps that are set"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = ctx.args().to_vec();
+
+        let args = match args.first().map(|arg| arg.as_str()) {
+            Some("-l") => return list(ctx),
+            Some("-p") | None => return print(ctx),
+            Some("--") => &args[1..],
+            _ => &args[..],
+        };
+
+        // a lone condition, or an integer first, resets every condition.
+        let (action, conditions) = match args {
+            [] => return print(ctx),
+            [_] => (None, args),
+            [action, conditions @ ..] if action == "-" => (None, conditions),
+            [action, ..] if action.parse::<u32>().is_ok() => (None, args),
+            [action, conditions @ ..] => (Some(action.clone()), conditions),
+        };
+
+        let mut status = 0;
+
+        for condition in conditions {
+            let name = match condition.to_ascii_uppercase().as_str() {
+                "ERR" => "ERR",
+                _ => match signal::number(condition).and_then(signal::name) {
+                    Some(name) => name,
+                    None => {
+                        status = ctx.error(&format!("{condition}: invalid signal specification"));
+                        continue;
+                    }
+                },
+            };
+
+            let number = signal::number(name).unwrap_or(0);
+
+            if number == libc::SIGKILL || number == libc::SIGSTOP {
+                status = ctx.error(&format!("{name}: cannot be trapped"));
+                continue;
+            }
+
+            if number != 0 {
+                match action.as_deref() {
+                    Some("") => signal::ignore(number),
+                    Some(_) => signal::catch(number),
+                    None if signal::SHELL.contains(&number) => signal::catch(number),
+                    None => signal::restore(number),
+                }
+            }
+
+            ctx.state_mut().set_trap(name, action.clone());
+        }
+
+        status
+    }
+}
+
+fn print(ctx: &mut Context) -> i32 {
+    let output = ctx
+        .state()
+        .traps()
+        .iter()
+        .map(|(name, action)| format!("trap -- {} {name}\n", escape::quote(action)))
+        .collect::<String>();
+
+    ctx.write(&output)
+}
+
+fn list(ctx: &mut Context) -> i32 {
+    let output = signal::SIGNALS
+        .iter()
+        .filter(|(_, number)| *number != 0)
+        .map(|(name, number)| format!("{number:>2}) SIG{name}\n"))
+        .collect::<String>();
+
+    ctx.write(&output)
+}
@@ -0,0 +1,75 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::options;
+use crate::path;
+use crate::path::Kind;
+use std::io::Write;
+
+pub struct Type;
+
+impl Builtin for Type {
+    fn name(&self) -> &'static str {
+        "type"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "type [-atp] name..."
+    }
+
+    fn description(&self) -> &'static str {
+        "Tell how each name would be run: as an alias, a function, a builtin or a \
+         file. -a lists every match, -t prints only the kind and -p only the path \
+         of files."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-a", "list every match"),
+            ("-t", "print only the kind"),
+            ("-p", "print only the path of files"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, names) = match options::short_flags(ctx.args(), "atp") {
+            Ok((flags, names)) => (flags, names.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        let mut status = 0;
+
+        for name in names {
+            let kinds = path::resolve(&*ctx.shell, &name, flags.contains(&'a'));
+
+            if kinds.is_empty() {
+                if flags.contains(&'t') == false && flags.contains(&'p') == false {
+                    ctx.error(&format!("{name}: not found"));
+                }
+
+                status = 1;
+                continue;
+            }
+
+            for kind in kinds {
+                let line = match (kind, flags.contains(&'t'), flags.contains(&'p')) {
+                    (Kind::File(file), _, true) => file.display().to_string(),
+                    (_, _, true) => continue,
+                    (Kind::Alias(_), true, _) => "alias".to_owned(),
+                    (Kind::Function, true, _) => "function".to_owned(),
+                    (Kind::Builtin, true, _) => "builtin".to_owned(),
+                    (Kind::File(_), true, _) => "file".to_owned(),
+                    (Kind::Alias(text), false, _) => format!("{name} is aliased to `{text}'"),
+                    (Kind::Function, false, _) => format!("{name} is a function"),
+                    (Kind::Builtin, false, _) => format!("{name} is a shell builtin"),
+                    (Kind::File(file), false, _) => format!("{name} is {}", file.display()),
+                };
+
+                if let Err(err) = writeln!(ctx.stdout, "{line}") {
+                    return ctx.error(&format!("write error: {err}"));
+                }
+            }
+        }
+
+        status
+    }
+}
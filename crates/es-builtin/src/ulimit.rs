@@ -0,0 +1,274 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::options;
+use std::io;
+use std::io::Write;
+
+pub struct Ulimit;
+
+// a limit by its flag, with the unit of the values ulimit reads and prints.
+struct Resource {
+    flag: char,
+    // the RLIMIT_ constant, whose type differs between platforms.
+    resource: libc::c_int,
+    // bytes or seconds in one unit.
+    factor: libc::rlim_t,
+    description: &'static str,
+    unit: &'static str,
+}
+
+const RESOURCES: [Resource; 6] = [
+    Resource {
+        flag: 'c',
+        resource: libc::RLIMIT_CORE as libc::c_int,
+        factor: 1024,
+        description: "core file size",
+        unit: "kbytes",
+    },
+    Resource {
+        flag: 'f',
+        resource: libc::RLIMIT_FSIZE as libc::c_int,
+        factor: 1024,
+        description: "file size",
+        unit: "kbytes",
+    },
+    Resource {
+        flag: 'n',
+        resource: libc::RLIMIT_NOFILE as libc::c_int,
+        factor: 1,
+        description: "open files",
+        unit: "",
+    },
+    Resource {
+        flag: 't',
+        resource: libc::RLIMIT_CPU as libc::c_int,
+        factor: 1,
+        description: "cpu time",
+        unit: "seconds",
+    },
+    Resource {
+        flag: 'u',
+        resource: libc::RLIMIT_NPROC as libc::c_int,
+        factor: 1,
+        description: "max user processes",
+        unit: "",
+    },
+    Resource {
+        flag: 'v',
+        resource: libc::RLIMIT_AS as libc::c_int,
+        factor: 1024,
+        description: "virtual memory",
+        unit: "kbytes",
+    },
+];
+
+impl Builtin for Ulimit {
+    fn name(&self) -> &'static str {
+        "ulimit"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "ulimit [-SHa] [-cfntuv] [limit]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print or set a resource limit of the shell, which the commands it starts \
+         inherit: -c core file size and -f file size in kbytes, -n open files, \
+         -t cpu seconds, -u user processes and -v virtual memory in kbytes. -f is \
+         the default. limit is a number, unlimited, soft or hard. -S works on \
+         the soft limit and -H on the hard one, a new limit sets both unless one \
+         is given, printing shows the soft one. -a prints every limit."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-S", "the soft limit"),
+            ("-H", "the hard limit"),
+            ("-a", "print every limit"),
+            ("-c", "core file size in kbytes"),
+            ("-f", "file size in kbytes, the default"),
+            ("-n", "open files"),
+            ("-t", "cpu seconds"),
+            ("-u", "user processes"),
+            ("-v", "virtual memory in kbytes"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, args) = match options::short_flags(ctx.args(), "SHacfntuv") {
+            Ok((flags, args)) => (flags, args.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        let is_soft = flags.contains(&'S');
+
+        let is_hard = flags.contains(&'H');
+
+        let mut resources = RESOURCES
+            .iter()
+            .filter(|resource| flags.contains(&'a') || flags.contains(&resource.flag))
+            .collect::<Vec<&Resource>>();
+
+        if resources.is_empty() {
+            resources.push(&RESOURCES[1]);
+        }
+
+        let value = match args.as_slice() {
+            [] => None,
+            [value] if flags.contains(&'a') == false && resources.len() == 1 => Some(value),
+            _ => return ctx.error("too many arguments"),
+        };
+
+        let resource = resources[0];
+
+        let value = match value {
+            Some(value) => value,
+            None => {
+                let is_all = resources.len() > 1;
+
+                let mut output = String::new();
+
+                for resource in resources {
+                    let (soft, hard) = match get(resource) {
+                        Ok(limit) => limit,
+                        Err(err) => return ctx.error(&err.to_string()),
+                    };
+
+                    let limit = match is_hard && is_soft == false {
+                        true => hard,
+                        false => soft,
+                    };
+
+                    let limit = display(resource, limit);
+
+                    if is_all {
+                        let unit = match resource.unit {
+                            "" => format!("(-{})", resource.flag),
+                            unit => format!("({unit}, -{})", resource.flag),
+                        };
+
+                        output.push_str(&format!("{:<20}{unit:>16} ", resource.description));
+                    }
+
+                    output.push_str(&format!("{limit}\n"));
+                }
+
+                return match ctx.stdout.write_all(output.as_bytes()) {
+                    Ok(_) => 0,
+                    Err(err) => ctx.error(&format!("write error: {err}")),
+                };
+            }
+        };
+
+        let (soft, hard) = match get(resource) {
+            Ok(limit) => limit,
+            Err(err) => return ctx.error(&err.to_string()),
+        };
+
+        let limit = match value.as_str() {
+            "unlimited" => libc::RLIM_INFINITY,
+            "soft" => soft,
+            "hard" => hard,
+            value => match value.parse::<libc::rlim_t>() {
+                Ok(n) => n.saturating_mul(resource.factor),
+                Err(_) => return ctx.error(&format!("{value}: invalid number")),
+            },
+        };
+
+        // neither -S nor -H sets both.
+        let limit = libc::rlimit {
+            rlim_cur: match is_soft || is_hard == false {
+                true => limit,
+                false => soft,
+            },
+            rlim_max: match is_hard || is_soft == false {
+                true => limit,
+                false => hard,
+            },
+        };
+
+        if unsafe { libc::setrlimit(resource.resource as _, &limit) } < 0 {
+            let err = io::Error::last_os_error();
+
+            return ctx.error(&format!(
+                "{}: cannot modify limit: {err}",
+                resource.description
+            ));
+        }
+
+        0
+    }
+}
+
+// the soft and hard limit.
+fn get(resource: &Resource) -> io::Result<(libc::rlim_t, libc::rlim_t)> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+
+    match unsafe { libc::getrlimit(resource.resource as _, &mut limit) } {
+        0 => Ok((limit.rlim_cur, limit.rlim_max)),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+fn display(resource: &Resource, limit: libc::rlim_t) -> String {
+    match limit {
+        libc::RLIM_INFINITY => "unlimited".to_owned(),
+        limit => (limit / resource.factor).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestShell;
+
+    #[test]
+    fn limits_print_in_the_unit_of_the_resource() {
+        assert_eq!(display(&RESOURCES[1], libc::RLIM_INFINITY), "unlimited");
+        assert_eq!(display(&RESOURCES[1], 4096), "4");
+        assert_eq!(display(&RESOURCES[2], 1024), "1024");
+    }
+
+    #[test]
+    fn limits_are_read_for_each_flag() {
+        let (soft, _) = get(&RESOURCES[2]).unwrap();
+
+        let (stdout, _, status) = TestShell::new().run(&["ulimit", "-n"]);
+
+        assert_eq!(
+            (stdout, status),
+            (format!("{}\n", display(&RESOURCES[2], soft)), 0)
+        );
+
+        let (stdout, _, status) = TestShell::new().run(&["ulimit", "-a"]);
+
+        assert_eq!(status, 0);
+        assert_eq!(stdout.lines().count(), RESOURCES.len());
+        assert!(stdout.starts_with("core file size          (kbytes, -c) "));
+    }
+
+    #[test]
+    fn bad_arguments_are_errors() {
+        let mut shell = TestShell::new();
+
+        assert_eq!(
+            shell.run(&["ulimit", "-x"]),
+            ("".into(), "eash: ulimit: -x: invalid option\n".into(), 1)
+        );
+        assert_eq!(
+            shell.run(&["ulimit", "-n", "1", "2"]),
+            ("".into(), "eash: ulimit: too many arguments\n".into(), 1)
+        );
+        assert_eq!(
+            shell.run(&["ulimit", "-a", "1"]),
+            ("".into(), "eash: ulimit: too many arguments\n".into(), 1)
+        );
+        assert_eq!(
+            shell.run(&["ulimit", "-n", "many"]),
+            ("".into(), "eash: ulimit: many: invalid number\n".into(), 1)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::options;
+use std::io::Write;
+
+pub struct Umask;
+
+impl Builtin for Umask {
+    fn name(&self) -> &'static str {
+        "umask"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "umask [-pS] [mode]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Print or set the file mode creation mask of the shell, which the \
+         commands it starts inherit. mode is octal, such as 022, or symbolic, \
+         such as u=rwx,g=rx,o= or go-w, naming the permissions to allow. -S \
+         prints the mask in the symbolic form and -p as a umask command."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("-p", "print the mask as a umask command"),
+            ("-S", "print the mask in the symbolic form"),
+        ]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, args) = match options::short_flags(ctx.args(), "pS") {
+            Ok((flags, args)) => (flags, args.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        let mask = get();
+
+        let mode = match args.as_slice() {
+            [] => None,
+            [mode] => Some(mode),
+            _ => return ctx.error("too many arguments"),
+        };
+
+        let mode = match mode {
+            Some(mode) => mode,
+            None => {
+                let is_symbolic = flags.contains(&'S');
+
+                let mask = match is_symbolic {
+                    true => symbolic_string(mask),
+                    false => format!("{mask:04o}"),
+                };
+
+                let output = match flags.contains(&'p') {
+                    true if is_symbolic => format!("umask -S {mask}\n"),
+                    true => format!("umask {mask}\n"),
+                    false => format!("{mask}\n"),
+                };
+
+                return match ctx.stdout.write_all(output.as_bytes()) {
+                    Ok(_) => 0,
+                    Err(err) => ctx.error(&format!("write error: {err}")),
+                };
+            }
+        };
+
+        let is_octal = mode.starts_with(|ch: char| ch.is_ascii_digit());
+
+        let result = match is_octal {
+            true => match libc::mode_t::from_str_radix(mode, 8) {
+                Ok(mask) if mask <= 0o777 => Ok(mask),
+                _ => Err(format!("{mode}: octal number out of range")),
+            },
+            false => symbolic(mode, mask),
+        };
+
+        match result {
+            Ok(mask) => {
+                unsafe { libc::umask(mask) };
+                0
+            }
+            Err(err) => ctx.error(&err),
+        }
+    }
+}
+
+// umask can only be read by setting it, the old one is put back.
+fn get() -> libc::mode_t {
+    unsafe {
+        let mask = libc::umask(0);
+
+        libc::umask(mask);
+
+        mask
+    }
+}
+
+// the mask after clauses like u=rwx,g+r,o-w. they change the permissions the
+// mask allows, no who means all of them.
+fn symbolic(mode: &str, mask: libc::mode_t) -> Result<libc::mode_t, String> {
+    let invalid = || format!("{mode}: invalid symbolic mode");
+
+    let mut allowed = !mask & 0o777;
+
+    for clause in mode.split(',') {
+        let mut chars = clause.chars().peekable();
+
+        let mut who = 0;
+
+        while let Some(ch) = chars.next_if(|ch| "ugoa".contains(*ch)) {
+            who |= match ch {
+                'u' => 0o700,
+                'g' => 0o070,
+                'o' => 0o007,
+                _ => 0o777,
+            };
+        }
+
+        if who == 0 {
+            who = 0o777;
+        }
+
+        // at least one operator, each followed by its permissions.
+        if chars.peek().is_none() {
+            return Err(invalid());
+        }
+
+        while let Some(op) = chars.next() {
+            let mut perms = 0;
+
+            while let Some(ch) = chars.next_if(|ch| "rwx".contains(*ch)) {
+                perms |= match ch {
+                    'r' => 0o444,
+                    'w' => 0o222,
+                    _ => 0o111,
+                };
+            }
+
+            perms &= who;
+
+            match op {
+                '+' => allowed |= perms,
+                '-' => allowed &= !perms,
+                '=' => allowed = (allowed & !who) | perms,
+                _ => return Err(invalid()),
+            }
+        }
+    }
+
+    Ok(!allowed & 0o777)
+}
+
+// u=rwx,g=rx,o=rx for 022.
+fn symbolic_string(mask: libc::mode_t) -> String {
+    let allowed = !mask & 0o777;
+
+    [('u', 6), ('g', 3), ('o', 0)]
+        .iter()
+        .map(|(who, shift)| {
+            let bits = (allowed >> shift) & 0o7;
+
+            let perms = [(0o4, 'r'), (0o2, 'w'), (0o1, 'x')]
+                .iter()
+                .filter(|(bit, _)| bits & bit != 0)
+                .map(|(_, ch)| *ch)
+                .collect::<String>();
+
+            format!("{who}={perms}")
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbolic_modes_change_what_the_mask_allows() {
+        assert_eq!(symbolic("u=rwx,g=rx,o=", 0o022), Ok(0o027));
+        assert_eq!(symbolic("go-w", 0o000), Ok(0o022));
+        assert_eq!(symbolic("g+w", 0o022), Ok(0o002));
+        assert_eq!(symbolic("a=r", 0o022), Ok(0o333));
+        assert_eq!(symbolic("=rx", 0o000), Ok(0o222));
+        assert_eq!(symbolic("u-x+w", 0o077), Ok(0o177));
+        assert_eq!(symbolic("o=", 0o000), Ok(0o007));
+    }
+
+    #[test]
+    fn symbolic_modes_need_an_operator() {
+        assert_eq!(symbolic("u", 0o022), Err("u: invalid symbolic mode".into()));
+        assert_eq!(
+            symbolic("u=r,g", 0o022),
+            Err("u=r,g: invalid symbolic mode".into())
+        );
+        assert_eq!(
+            symbolic("u*r", 0o022),
+            Err("u*r: invalid symbolic mode".into())
+        );
+    }
+
+    #[test]
+    fn masks_print_as_what_they_allow() {
+        assert_eq!(symbolic_string(0o022), "u=rwx,g=rx,o=rx");
+        assert_eq!(symbolic_string(0o077), "u=rwx,g=,o=");
+        assert_eq!(symbolic_string(0o777), "u=,g=,o=");
+    }
+}
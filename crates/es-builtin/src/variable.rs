@@ -0,0 +1,245 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::escape;
+use crate::options;
+use crate::state;
+use crate::state::State;
+use std::io::Write;
+
+pub struct Export;
+
+pub struct Readonly;
+
+pub struct Local;
+
+pub struct Unset;
+
+impl Builtin for Export {
+    fn name(&self) -> &'static str {
+        "export"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "export [-p] [name[=value] ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Export each name to the environment of the commands the shell runs, \
+         assigning value first when given. Without names, or with -p, print the \
+         exported variables."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-p", "print the exported variables")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = match options::short_flags(ctx.args(), "p") {
+            Ok((_, args)) => args.to_vec(),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        if args.is_empty() {
+            let state = ctx.state();
+
+            let mut lines = state
+                .variables()
+                .into_iter()
+                .filter(|(name, _)| state.is_exported(name))
+                .map(|(name, value)| format!("export {name}={}\n", escape::quote(&value)))
+                .collect::<Vec<String>>();
+
+            lines.extend(
+                state
+                    .exported()
+                    .into_iter()
+                    .map(|name| format!("export {name}\n")),
+            );
+
+            lines.sort();
+
+            return print(ctx, &lines);
+        }
+
+        declare(ctx, &args, State::export)
+    }
+}
+
+impl Builtin for Readonly {
+    fn name(&self) -> &'static str {
+        "readonly"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "readonly [-p] [name[=value] ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mark each name readonly, assigning value first when given. A readonly \
+         variable can not be assigned or unset. Without names, or with -p, print \
+         the readonly variables."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-p", "print the readonly variables")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = match options::short_flags(ctx.args(), "p") {
+            Ok((_, args)) => args.to_vec(),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        if args.is_empty() {
+            let state = ctx.state();
+
+            let lines = state
+                .readonly()
+                .into_iter()
+                .map(|name| match state.variable(&name) {
+                    Some(value) => format!("readonly {name}={}\n", escape::quote(&value)),
+                    None => format!("readonly {name}\n"),
+                })
+                .collect::<Vec<String>>();
+
+            return print(ctx, &lines);
+        }
+
+        declare(ctx, &args, State::set_readonly)
+    }
+}
+
+impl Builtin for Local {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "local name[=value] ..."
+    }
+
+    fn description(&self) -> &'static str {
+        "Make each name local to the function being run, assigning value when \
+         given. The variable gets its old value back once the function returns."
+    }
+
+    // the value is assigned after the variable was made local, or it would be
+    // kept on return.
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = ctx.args().to_vec();
+
+        let mut status = 0;
+
+        for arg in &args {
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_owned())),
+                None => (arg.as_str(), None),
+            };
+
+            if state::is_name(name) == false {
+                status = ctx.error(&format!("`{arg}': not a valid identifier"));
+                continue;
+            }
+
+            if ctx.state_mut().local(name) == false {
+                return ctx.error("can only be used in a function");
+            }
+
+            if let Some(value) = value {
+                if let Err(err) = ctx.state_mut().assign(name, value) {
+                    status = ctx.error(&err);
+                }
+            }
+        }
+
+        status
+    }
+}
+
+impl Builtin for Unset {
+    fn name(&self) -> &'static str {
+        "unset"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "unset [-f | -v] name ..."
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove each variable, with -v, or function, with -f. Without either a \
+         name is taken as a variable when one is set, as a function otherwise."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-f", "remove a function"), ("-v", "remove a variable")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, args) = match options::short_flags(ctx.args(), "fv") {
+            Ok((flags, args)) => (flags, args.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        let mut status = 0;
+
+        for name in &args {
+            let is_function = match flags.last() {
+                Some('f') => true,
+                Some(_) => false,
+                None => {
+                    ctx.state().variable(name).is_none() && ctx.state().function(name).is_some()
+                }
+            };
+
+            if is_function {
+                ctx.state_mut().unset_function(name);
+                continue;
+            }
+
+            if ctx.state().is_readonly(name) {
+                status = ctx.error(&format!("{name}: cannot unset: readonly variable"));
+                continue;
+            }
+
+            ctx.state_mut().unset_variable(name);
+        }
+
+        status
+    }
+}
+
+// the name=value arguments of export and readonly. each name is assigned its
+// value, if any, and then marked.
+fn declare(ctx: &mut Context, args: &[String], mark: fn(&mut State, &str)) -> i32 {
+    let mut status = 0;
+
+    for arg in args {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_owned())),
+            None => (arg.as_str(), None),
+        };
+
+        if state::is_name(name) == false {
+            status = ctx.error(&format!("`{arg}': not a valid identifier"));
+            continue;
+        }
+
+        if let Some(value) = value {
+            if let Err(err) = ctx.state_mut().assign(name, value) {
+                status = ctx.error(&err);
+                continue;
+            }
+        }
+
+        mark(ctx.state_mut(), name);
+    }
+
+    status
+}
+
+fn print(ctx: &mut Context, lines: &[String]) -> i32 {
+    match ctx.stdout.write_all(lines.concat().as_bytes()) {
+        Ok(_) => 0,
+        Err(err) => ctx.error(&format!("write error: {err}")),
+    }
+}
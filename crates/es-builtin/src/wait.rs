@@ -0,0 +1,54 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+
+pub struct Wait;
+
+impl Builtin for Wait {
+    fn name(&self) -> &'static str {
+        "wait"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "wait [pid | %job ...]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Wait for background jobs, given by pid or by %n, %% or %+ for the latest \
+         and %- for the one before, and return the status of the last one. \
+         Without arguments wait for every background job and return 0. The \
+         status is 127 when a pid is not a job of the shell."
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let args = ctx.args().to_vec();
+
+        if args.is_empty() {
+            return ctx.shell.wait(None).unwrap_or(0);
+        }
+
+        let mut status = 0;
+
+        for arg in &args {
+            let pid = match arg.strip_prefix('%') {
+                Some(job) => ctx.shell.job(job),
+                None => match arg.parse::<libc::pid_t>() {
+                    Ok(pid) => Some(pid),
+                    Err(_) => {
+                        status = ctx.error(&format!("{arg}: not a pid or valid job spec"));
+                        continue;
+                    }
+                },
+            };
+
+            status = match pid.and_then(|pid| ctx.shell.wait(Some(pid))) {
+                Some(status) => status,
+                None => {
+                    ctx.error(&format!("{arg}: no such job"));
+                    127
+                }
+            };
+        }
+
+        status
+    }
+}
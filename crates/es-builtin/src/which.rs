@@ -0,0 +1,61 @@
+use crate::builtin::Builtin;
+use crate::builtin::Context;
+use crate::options;
+use crate::path;
+use crate::path::Kind;
+use std::io::Write;
+
+pub struct Which;
+
+impl Builtin for Which {
+    fn name(&self) -> &'static str {
+        "which"
+    }
+
+    fn synopsis(&self) -> &'static str {
+        "which [-a] name..."
+    }
+
+    fn description(&self) -> &'static str {
+        "Print the path of the program each name runs, or say it is an alias, a \
+         function or a builtin. -a prints every match."
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[("-a", "print every match")]
+    }
+
+    fn run(&self, ctx: &mut Context) -> i32 {
+        let (flags, names) = match options::short_flags(ctx.args(), "a") {
+            Ok((flags, names)) => (flags, names.to_vec()),
+            Err(flag) => return ctx.error(&format!("-{flag}: invalid option")),
+        };
+
+        let mut status = 0;
+
+        for name in names {
+            let kinds = path::resolve(&*ctx.shell, &name, flags.contains(&'a'));
+
+            if kinds.is_empty() {
+                let _ = writeln!(ctx.stderr, "{name} not found");
+                status = 1;
+                continue;
+            }
+
+            for kind in kinds {
+                let line = match kind {
+                    Kind::Alias(text) => format!("{name}: aliased to {text}"),
+                    Kind::Function => format!("{name}: shell function"),
+                    Kind::Builtin => format!("{name}: shell builtin"),
+                    Kind::File(file) => file.display().to_string(),
+                };
+
+                if let Err(err) = writeln!(ctx.stdout, "{line}") {
+                    return ctx.error(&format!("write error: {err}"));
+                }
+            }
+        }
+
+        status
+    }
+}
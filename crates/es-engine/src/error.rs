@@ -1,3 +1,5 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -22,10 +24,32 @@ impl Error {
     pub fn kind(&self) -> &ErrorKind {
         &self.kind
     }
+
+    // exit status of a command that failed with this error.
+    pub fn status(&self) -> i32 {
+        match self.kind {
+            ErrorKind::CommandNotFound => 127,
+            ErrorKind::PermissionDenied => 126,
+            _ => 1,
+        }
+    }
 }
 
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
 #[derive(Debug, Clone)]
 pub enum ErrorKind {
     Unknown,
-    ExecutionFailedg
+    ExecutionFailedg,
+    CommandNotFound,
+    PermissionDenied,
+    Redirect,
+    UnboundVariable,
+    Readonly,
 }
@@ -0,0 +1,132 @@
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::error::Result;
+use es_ast::Expression;
+use es_builtin::path;
+use es_builtin::Setting;
+use es_builtin::State;
+
+const DEFAULT_IFS: &str = " \t\n";
+
+// the string value of a leaf expression. unset variables are empty, or an
+// error with set -u.
+pub fn value(state: &State, expr: &Expression) -> Result<String> {
+    Ok(match expr {
+        Expression::String(string) => expand(state, string)?,
+        Expression::Variable(name) => variable(state, name)?,
+        Expression::Number(number) => number.to_string(),
+        Expression::Boolean(boolean) => boolean.to_string(),
+        Expression::FD(fd) => format!("&{fd}"),
+        _ => String::new(),
+    })
+}
+
+fn variable(state: &State, name: &str) -> Result<String> {
+    match state.variable(name) {
+        Some(value) => Ok(value),
+        None if state.is_set(Setting::Nounset) && matches!(name, "@" | "*") == false => {
+            Err(Error::new(
+                ErrorKind::UnboundVariable,
+                format!("{name}: unbound variable"),
+            ))
+        }
+        None => Ok(String::new()),
+    }
+}
+
+// a string with a leading tilde prefix expanded and variables interpolated.
+fn expand(state: &State, string: &str) -> Result<String> {
+    if string.starts_with('~') == false {
+        return interpolate(state, string);
+    }
+
+    let (prefix, rest) = match string.find('/') {
+        Some(i) => string.split_at(i),
+        None => (string, ""),
+    };
+
+    Ok(format!(
+        "{}{}",
+        path::tilde(state, prefix),
+        interpolate(state, rest)?
+    ))
+}
+
+// the arguments a leaf expression expands to. a bare variable is split into
+// fields on IFS, everything else is a single argument.
+pub fn words(state: &State, expr: &Expression) -> Result<Vec<String>> {
+    match expr {
+        Expression::Variable(_) => {
+            let ifs = state.variable("IFS").unwrap_or(DEFAULT_IFS.to_owned());
+
+            Ok(split(&value(state, expr)?, &ifs))
+        }
+        _ => Ok(vec![value(state, expr)?]),
+    }
+}
+
+pub fn split(string: &str, ifs: &str) -> Vec<String> {
+    string
+        .split(|ch| ifs.contains(ch))
+        .filter(|field| field.is_empty() == false)
+        .map(|field| field.to_owned())
+        .collect()
+}
+
+// replaces $name and ${name} in a string by the value of the variable. a `$`
+// that does not start a name is kept as it is.
+pub fn interpolate(state: &State, string: &str) -> Result<String> {
+    if string.contains('$') == false {
+        return Ok(string.to_owned());
+    }
+
+    let mut output = String::new();
+
+    let mut chars = string.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '$' {
+            output.push(ch);
+            continue;
+        }
+
+        let mut name = String::new();
+
+        match chars.peek() {
+            Some('{') => {
+                chars.next();
+
+                for ch in chars.by_ref() {
+                    if ch == '}' {
+                        break;
+                    }
+
+                    name.push(ch);
+                }
+            }
+
+            Some(ch) if matches!(ch, '?' | '#' | '@' | '*' | '$' | '!') || ch.is_ascii_digit() => {
+                name.push(*ch);
+                chars.next();
+            }
+
+            _ => {
+                while let Some(ch) = chars.peek() {
+                    if ch.is_alphanumeric() == false && *ch != '_' {
+                        break;
+                    }
+
+                    name.push(*ch);
+                    chars.next();
+                }
+            }
+        }
+
+        match name.is_empty() {
+            true => output.push('$'),
+            false => output.push_str(&variable(state, &name)?),
+        }
+    }
+
+    Ok(output)
+}
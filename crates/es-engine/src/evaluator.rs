@@ -0,0 +1,1164 @@
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::error::Result;
+use crate::eval;
+use crate::process;
+use crate::process::Process;
+use crate::time;
+use es_ast::*;
+use es_builtin::compare;
+use es_builtin::escape;
+use es_builtin::help;
+use es_builtin::io::Fd;
+use es_builtin::signal;
+use es_builtin::Context;
+use es_builtin::Lookup;
+use es_builtin::Registry;
+use es_builtin::Setting;
+use es_builtin::Shell;
+use es_builtin::State;
+use es_parser::diagnostic::Diagnostic;
+use es_parser::error::Error as ParseError;
+use es_parser::lexer::Lexer;
+use es_parser::Parser;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::IntoRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+use std::time::Instant;
+
+// how a statement left the block it ran in.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Flow {
+    Next,
+    Return,
+    Exit,
+    // ctrl-c, everything up to the prompt or the end of the script stops.
+    Interrupt,
+}
+
+// fds the commands of the current scope read and write. a function called with
+// redirections runs with its own.
+#[derive(Debug, Clone, Copy)]
+struct Io {
+    stdin: Fd,
+    stdout: Fd,
+    stderr: Fd,
+}
+
+// a command ready to run: expanded argv and the fds it gets, with the files its
+// redirections opened.
+struct Prepared {
+    argv: Vec<String>,
+    fds: BTreeMap<u32, RawFd>,
+    files: Vec<OwnedFd>,
+}
+
+// the file source or eval is running, errors name it and the line.
+struct Source {
+    name: String,
+    line: usize,
+}
+
+// a background pipeline and the number %n refers to it by.
+struct Job {
+    number: usize,
+    process: Process,
+}
+
+// a pipeline stage that finished in the shell or runs on its own.
+enum Stage {
+    Done(i32),
+    Running(Process),
+}
+
+pub struct Evaluator {
+    state: State,
+    registry: Registry,
+    io: Io,
+    background: Vec<Job>,
+    // statuses of background jobs that ended before wait asked for them.
+    finished: HashMap<libc::pid_t, i32>,
+    // the number the next background job gets.
+    next_job: usize,
+    source: Option<Source>,
+    // how many left sides of && and || are being run, set -e leaves their
+    // failures alone.
+    checked: usize,
+    // whether the status of the last statement came from such a left side.
+    is_short_circuit: bool,
+    // set by SIGINT without a trap, until eval returns.
+    is_interrupted: bool,
+    // an ERR trap does not run for the commands of a trap.
+    is_trapping: bool,
+    // reading commands from a terminal. errors such as an unbound variable
+    // under set -u end a script but not an interactive shell.
+    is_interactive: bool,
+}
+
+impl Evaluator {
+    pub fn new(state: State, registry: Registry) -> Self {
+        Self {
+            state: state,
+            registry: registry,
+            io: Io {
+                stdin: Fd::STDIN,
+                stdout: Fd::STDOUT,
+                stderr: Fd::STDERR,
+            },
+            background: Vec::new(),
+            finished: HashMap::new(),
+            next_job: 1,
+            source: None,
+            checked: 0,
+            is_short_circuit: false,
+            is_interrupted: false,
+            is_trapping: false,
+            is_interactive: false,
+        }
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    pub fn registry_mut(&mut self) -> &mut Registry {
+        &mut self.registry
+    }
+
+    pub fn set_interactive(&mut self, is_interactive: bool) {
+        self.is_interactive = is_interactive;
+    }
+
+    // runs a script and returns its exit status. check state().exit_code() to
+    // see whether it asked the shell to exit.
+    pub fn eval(&mut self, block: &Block) -> i32 {
+        self.eval_block(block);
+
+        self.finish()
+    }
+
+    // eval for a whole script parsed from source, so that an alias it defines
+    // applies to the statements after it. returns the syntax errors when the
+    // rest of the script no longer parses with the new aliases. errors name the
+    // script and the line, except for lines typed at the prompt.
+    pub fn eval_script(
+        &mut self,
+        block: Block,
+        name: &str,
+        source: &str,
+    ) -> std::result::Result<i32, Vec<ParseError>> {
+        if self.is_interactive == false {
+            self.source = Some(Source {
+                name: name.to_owned(),
+                line: 0,
+            });
+        }
+
+        self.eval_source(block, source)?;
+
+        Ok(self.finish())
+    }
+
+    fn finish(&mut self) -> i32 {
+        // a ctrl-c that ended the last command.
+        self.handle_signals();
+
+        if self.is_interrupted {
+            self.is_interrupted = false;
+
+            self.state.set_status(128 + libc::SIGINT);
+        }
+
+        self.state.exit_code().unwrap_or(self.state.status())
+    }
+
+    // installs the handlers of the shell, without them a signal does what it
+    // does to any process.
+    pub fn catch_signals(&mut self) {
+        for number in signal::SHELL {
+            signal::catch(number);
+        }
+    }
+
+    // runs the traps of the signals that arrived since the last call. without
+    // a trap SIGINT interrupts what runs and SIGHUP is passed on to background
+    // jobs before the shell exits.
+    pub fn handle_signals(&mut self) {
+        for number in signal::take_all() {
+            if number == libc::SIGCHLD {
+                self.reap();
+            }
+
+            let name = signal::name(number).unwrap_or_default();
+
+            if self.state.trap(name).is_some() {
+                self.run_trap(name);
+                continue;
+            }
+
+            match number {
+                libc::SIGINT => self.is_interrupted = true,
+                libc::SIGHUP => {
+                    for job in &self.background {
+                        job.process.signal(libc::SIGHUP);
+                    }
+
+                    if self.state.exit_code().is_none() {
+                        self.state.exit(128 + libc::SIGHUP);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // runs the EXIT trap once, before the shell exits with status. exit in the
+    // trap changes the status.
+    pub fn exit_trap(&mut self, status: i32) -> i32 {
+        if self.state.trap("EXIT").is_none() {
+            return status;
+        }
+
+        self.state.take_exit();
+
+        self.state.set_status(status);
+
+        self.run_trap("EXIT");
+
+        self.state.set_trap("EXIT", None);
+
+        self.state.exit_code().unwrap_or(status)
+    }
+
+    // $? is left as it was before the trap, unless it exits.
+    fn run_trap(&mut self, condition: &str) {
+        let action = match self.state.trap(condition) {
+            Some(action) if action.is_empty() == false => action.to_owned(),
+            _ => return,
+        };
+
+        let status = self.state.status();
+
+        let is_trapping = std::mem::replace(&mut self.is_trapping, true);
+
+        let io = [self.io.stdin, self.io.stdout, self.io.stderr];
+
+        self.source("trap", &action, None, io);
+
+        self.is_trapping = is_trapping;
+
+        self.state.set_status(status);
+    }
+
+    // runs the top level statements of source one at a time. once one of them
+    // changes the aliases, source is parsed again with them and the statements
+    // after it are taken from the new block.
+    fn eval_source(
+        &mut self,
+        mut block: Block,
+        source: &str,
+    ) -> std::result::Result<Flow, Vec<ParseError>> {
+        let mut i = 0;
+
+        while let Some(statement) = block.statements().get(i) {
+            let end = statement.span().end();
+
+            let aliases = self.state.aliases().clone();
+
+            match self.eval_statement(statement) {
+                Flow::Next => {}
+                flow => return Ok(flow),
+            }
+
+            i += 1;
+
+            if self.state.aliases() == &aliases {
+                continue;
+            }
+
+            let mut parser = Parser::new(Lexer::new(source));
+
+            parser.set_aliases(self.state.aliases().clone());
+
+            let (reparsed, errors) = parser.parse();
+
+            if errors.is_empty() == false {
+                return Err(errors);
+            }
+
+            block = reparsed;
+
+            i = block
+                .statements()
+                .iter()
+                .position(|statement| statement.span().start() >= end)
+                .unwrap_or(block.statements().len());
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn eval_block(&mut self, block: &Block) -> Flow {
+        for statement in block {
+            match self.eval_statement(statement) {
+                Flow::Next => continue,
+                flow => return flow,
+            }
+        }
+
+        Flow::Next
+    }
+
+    fn eval_statement(&mut self, statement: &Statement) -> Flow {
+        self.handle_signals();
+
+        if self.is_interrupted {
+            return Flow::Interrupt;
+        }
+
+        self.reap();
+
+        self.is_short_circuit = false;
+
+        if let Some(source) = &mut self.source {
+            source.line = statement.span().line();
+        }
+
+        let result = match statement {
+            Statement::Assign(node) => self.eval_assign(node).map(|_| (Flow::Next, 0)),
+
+            Statement::If(node) => self.eval_if(node),
+
+            Statement::Loop(node) => self.eval_loop(node),
+
+            Statement::Block(node) => Ok((self.eval_block(node), self.state.status())),
+
+            Statement::Def(node) => {
+                self.state.set_function(node.clone());
+
+                Ok((Flow::Next, 0))
+            }
+
+            Statement::Return(node) => match node.expr() {
+                Some(expr) => eval::value(&self.state, expr).map(|value| {
+                    let status = match value.as_str() {
+                        "true" => 0,
+                        "false" => 1,
+                        value => value.parse::<i32>().unwrap_or(0),
+                    };
+
+                    (Flow::Return, status)
+                }),
+                None => Ok((Flow::Return, self.state.status())),
+            },
+
+            Statement::Expression(expr) => self
+                .eval_expression(expr)
+                .map(|status| (Flow::Next, status)),
+        };
+
+        let flow = match result {
+            Ok((flow, status)) => {
+                self.state.set_status(status);
+                flow
+            }
+            Err(err) => {
+                self.report(&err);
+                self.state.set_status(err.status());
+                Flow::Next
+            }
+        };
+
+        // a failed command runs the ERR trap and with set -e stops the shell,
+        // unless it was checked by && or ||. blocks, ifs and loops stop at the
+        // command inside them.
+        let is_failed = self.state.status() != 0
+            && self.checked == 0
+            && self.is_short_circuit == false
+            && matches!(statement, Statement::Expression(_));
+
+        if is_failed && self.is_trapping == false && self.state.exit_code().is_none() {
+            self.run_trap("ERR");
+        }
+
+        let is_errexit = is_failed && self.state.is_set(Setting::Errexit);
+
+        if is_errexit && self.state.exit_code().is_none() {
+            self.state.exit(self.state.status());
+        }
+
+        match self.state.exit_code() {
+            Some(_) => Flow::Exit,
+            None => flow,
+        }
+    }
+
+    fn eval_assign(&mut self, node: &Assign) -> Result<()> {
+        let value = eval::value(&self.state, node.expr())?;
+
+        if let Expression::Variable(name) = node.identify() {
+            self.trace(&[format!("{name}={value}")]);
+
+            self.state
+                .assign(name, value)
+                .map_err(|err| Error::new(ErrorKind::Readonly, err))?;
+        }
+
+        Ok(())
+    }
+
+    fn eval_if(&mut self, node: &If) -> Result<(Flow, i32)> {
+        if self.condition(node.comparison())? {
+            return Ok((self.eval_block(node.block()), self.state.status()));
+        }
+
+        for child in node.children() {
+            if self.condition(child.comparison())? {
+                return Ok((self.eval_block(child.block()), self.state.status()));
+            }
+        }
+
+        match node.else_block() {
+            Some(block) => Ok((self.eval_block(block), self.state.status())),
+            None => Ok((Flow::Next, 0)),
+        }
+    }
+
+    fn eval_loop(&mut self, node: &Loop) -> Result<(Flow, i32)> {
+        let mut status = 0;
+
+        while self.condition(node.comparison())? {
+            let flow = self.eval_block(node.block());
+
+            status = self.state.status();
+
+            if flow != Flow::Next {
+                return Ok((flow, status));
+            }
+        }
+
+        Ok((Flow::Next, status))
+    }
+
+    fn condition(&self, node: &Comparison) -> Result<bool> {
+        Ok(compare::compare(
+            *node.kind(),
+            &eval::value(&self.state, node.left())?,
+            &eval::value(&self.state, node.right())?,
+        ))
+    }
+
+    fn eval_expression(&mut self, expr: &Expression) -> Result<i32> {
+        match expr {
+            Expression::Command(_) | Expression::Pipe(_) => self.eval_pipeline(expr),
+
+            Expression::Logical(node) => {
+                self.checked += 1;
+
+                let status = self.eval_expression(node.left());
+
+                self.checked -= 1;
+
+                let status = status?;
+
+                self.state.set_status(status);
+
+                if self.state.exit_code().is_some() {
+                    return Ok(status);
+                }
+
+                match (node.kind(), status == 0) {
+                    (LogicalKind::And, true) | (LogicalKind::Or, false) => {
+                        self.is_short_circuit = false;
+
+                        self.eval_expression(node.right())
+                    }
+                    _ => {
+                        self.is_short_circuit = true;
+
+                        Ok(status)
+                    }
+                }
+            }
+
+            Expression::Time(node) => self.eval_time(node),
+
+            Expression::Comparison(node) => Ok(match self.condition(node)? {
+                true => 0,
+                false => 1,
+            }),
+
+            _ => Ok(self.state.status()),
+        }
+    }
+
+    // runs the pipeline, then prints its real, user and sys time to stderr in
+    // the format of TIMEFORMAT. an empty format prints nothing.
+    fn eval_time(&mut self, node: &Time) -> Result<i32> {
+        let start = Instant::now();
+
+        let (user, sys) = time::usage();
+
+        let status = self.eval_expression(node.pipeline());
+
+        let real = start.elapsed();
+
+        let (end_user, end_sys) = time::usage();
+
+        let format = self
+            .state
+            .variable("TIMEFORMAT")
+            .unwrap_or(time::DEFAULT_FORMAT.to_owned());
+
+        if format.is_empty() == false {
+            let user = end_user.saturating_sub(user);
+
+            let sys = end_sys.saturating_sub(sys);
+
+            let _ = writeln!(self.io.stderr, "{}", time::format(&format, real, user, sys));
+        }
+
+        status
+    }
+
+    // runs the commands of a pipeline side by side and returns the status of the
+    // last one. the last command runs in the shell itself when it is a builtin
+    // or function, the others in forks, so `... | read` sets its variables.
+    fn eval_pipeline(&mut self, expr: &Expression) -> Result<i32> {
+        let mut commands = Vec::new();
+
+        flatten(expr, &mut commands);
+
+        let is_background = commands.last().is_some_and(|command| {
+            command
+                .suffix()
+                .iter()
+                .any(|expr| matches!(expr, Expression::Background(true)))
+        });
+
+        let mut stages = Vec::new();
+
+        let mut stdin: Option<OwnedFd> = None;
+
+        for (i, command) in commands.iter().enumerate() {
+            let is_last = i + 1 == commands.len();
+
+            let (next, write) = match is_last {
+                true => (None, None),
+                false => {
+                    let (read, write) = process::pipe()?;
+                    (Some(read), Some(write))
+                }
+            };
+
+            let io = Io {
+                stdin: stdin
+                    .as_ref()
+                    .map(|fd| Fd::new(fd.as_raw_fd()))
+                    .unwrap_or(self.io.stdin),
+                stdout: write
+                    .as_ref()
+                    .map(|fd| Fd::new(fd.as_raw_fd()))
+                    .unwrap_or(self.io.stdout),
+                stderr: self.io.stderr,
+            };
+
+            // exec at the end of a pipeline replaces its stage, not the shell.
+            let is_exec = commands.len() > 1
+                && matches!(command.prefix(), Expression::String(name) if name == "exec");
+
+            let is_fork = is_last == false || is_background || is_exec;
+
+            let stage = self.eval_command(command, io, is_fork);
+
+            match stage {
+                Ok(stage) => stages.push(stage),
+                Err(err) => {
+                    self.report(&err);
+                    stages.push(Stage::Done(err.status()));
+                }
+            }
+
+            // the parent keeps no end of a pipe it handed out, or the reader
+            // would never see the end of input.
+            drop(write);
+
+            stdin = next;
+        }
+
+        let mut status = 0;
+
+        let mut failed = 0;
+
+        for stage in stages {
+            status = match stage {
+                Stage::Done(status) => status,
+                Stage::Running(process) if is_background => {
+                    self.state.set_last_pid(process.id() as u32);
+
+                    // numbers start over once every job ended and was waited for.
+                    if self.background.is_empty() && self.finished.is_empty() {
+                        self.next_job = 1;
+                    }
+
+                    self.background.push(Job {
+                        number: self.next_job,
+                        process: process,
+                    });
+
+                    self.next_job += 1;
+
+                    0
+                }
+                Stage::Running(process) => process.wait(),
+            };
+
+            if status != 0 {
+                failed = status;
+            }
+        }
+
+        // set -o pipefail: the status of the last command that failed.
+        match self.state.is_set(Setting::Pipefail) {
+            true => Ok(failed),
+            false => Ok(status),
+        }
+    }
+
+    // a program always runs in a child. builtins and functions run in the shell,
+    // or in a fork of it when is_fork is set.
+    fn eval_command(&mut self, command: &Command, io: Io, is_fork: bool) -> Result<Stage> {
+        let mut prepared = self.prepare(command, io)?;
+
+        let name = match prepared.argv.first() {
+            Some(name) => name.clone(),
+            None => return Ok(Stage::Done(0)),
+        };
+
+        self.trace(&prepared.argv);
+
+        let is_exec = name == "exec" && self.state.function(&name).is_none();
+
+        let is_internal = self.state.function(&name).is_some() || self.registry.contains(&name);
+
+        if is_internal == false {
+            return process::spawn(&prepared.argv, &prepared.fds).map(Stage::Running);
+        }
+
+        let command_io = Io {
+            stdin: Fd::new(prepared.fds.get(&0).copied().unwrap_or(-1)),
+            stdout: Fd::new(prepared.fds.get(&1).copied().unwrap_or(-1)),
+            stderr: Fd::new(prepared.fds.get(&2).copied().unwrap_or(-1)),
+        };
+
+        if is_fork == false {
+            // the redirections of exec stay for the rest of the shell, or go to
+            // the command that replaces it. they are undone when it can not be
+            // run.
+            let saved = match is_exec {
+                true => redirect(&mut prepared, io)?,
+                false => Vec::new(),
+            };
+
+            let status = self.call(&prepared.argv, command_io, Lookup::All);
+
+            // exec only returns when it failed.
+            if status != 0 {
+                restore(saved);
+            }
+
+            return Ok(Stage::Done(status));
+        }
+
+        match process::fork()? {
+            Some(pid) => Ok(Stage::Running(Process::Fork(pid))),
+            None => {
+                // exec in a pipeline only changes the process of its stage.
+                if is_exec {
+                    if let Err(err) = redirect(&mut prepared, io) {
+                        self.report(&err);
+
+                        unsafe { libc::_exit(err.status()) }
+                    }
+                }
+
+                let status = self.call(&prepared.argv, command_io, Lookup::All);
+
+                let status = self.state.exit_code().unwrap_or(status);
+
+                unsafe { libc::_exit(status) }
+            }
+        }
+    }
+
+    // runs a function or builtin in the shell. functions are only looked up
+    // with Lookup::All, command and builtin skip them.
+    fn call(&mut self, argv: &[String], io: Io, lookup: Lookup) -> i32 {
+        if let Some(def) = self
+            .state
+            .function(&argv[0])
+            .filter(|_| lookup == Lookup::All)
+        {
+            let def = def.clone();
+
+            return self.call_function(&def, argv, io);
+        }
+
+        let builtin = match self.registry.get(&argv[0]) {
+            Some(builtin) => builtin,
+            None => return 127,
+        };
+
+        // --help as the only argument describes the builtin, unless it is an
+        // operand of it.
+        if argv.len() == 2 && argv[1] == "--help" && builtin.has_help() {
+            let mut stdout = io.stdout;
+
+            return match write!(stdout, "{}", help::text(builtin.as_ref())) {
+                Ok(()) => 0,
+                Err(_) => 1,
+            };
+        }
+
+        let mut ctx = Context {
+            argv: argv,
+            stdin: io.stdin,
+            stdout: io.stdout,
+            stderr: io.stderr,
+            shell: self,
+        };
+
+        builtin.run(&mut ctx)
+    }
+
+    // parameters are set as variables for the call and put back afterwards,
+    // the arguments are also the positional parameters.
+    fn call_function(&mut self, def: &Def, argv: &[String], io: Io) -> i32 {
+        let args = argv[1..].to_vec();
+
+        self.state.push_scope();
+
+        for (i, param) in def.params().iter().enumerate() {
+            self.state.local(param);
+
+            self.state
+                .set_variable(param, args.get(i).cloned().unwrap_or_default());
+        }
+
+        let saved_args = self.state.set_args(args);
+
+        let saved_io = std::mem::replace(&mut self.io, io);
+
+        self.eval_block(def.block());
+
+        self.io = saved_io;
+
+        self.state.set_args(saved_args);
+
+        self.state.pop_scope();
+
+        self.state.status()
+    }
+
+    // expands the words of a command and opens its redirections, left to right.
+    fn prepare(&self, command: &Command, io: Io) -> Result<Prepared> {
+        let mut argv = eval::words(&self.state, command.prefix())?;
+
+        let mut fds = BTreeMap::from([
+            (0, io.stdin.raw()),
+            (1, io.stdout.raw()),
+            (2, io.stderr.raw()),
+        ]);
+
+        let mut files = Vec::new();
+
+        for expr in command.suffix() {
+            match expr {
+                Expression::Redirect(redirect) => {
+                    let target = match redirect.left() {
+                        Expression::FD(fd) => *fd,
+                        _ => match redirect.kind() {
+                            RedirectKind::Write => 1,
+                            RedirectKind::Read => 0,
+                        },
+                    };
+
+                    let source = match redirect.right() {
+                        Expression::FD(fd) => match fds.get(fd) {
+                            Some(source) => *source,
+                            None if is_exec_fd(*fd as RawFd) => *fd as RawFd,
+                            None => Err(Error::new(
+                                ErrorKind::Redirect,
+                                format!("&{fd}: bad file descriptor"),
+                            ))?,
+                        },
+                        right => {
+                            let path = eval::value(&self.state, right)?;
+
+                            let is_noclobber = self.state.is_set(Setting::Noclobber);
+
+                            let file = open(*redirect.kind(), &path, is_noclobber)?;
+
+                            let source = file.as_raw_fd();
+
+                            files.push(OwnedFd::from(file));
+
+                            source
+                        }
+                    };
+
+                    fds.insert(target, source);
+                }
+
+                Expression::Background(_) => {}
+
+                expr => argv.extend(eval::words(&self.state, expr)?),
+            }
+        }
+
+        Ok(Prepared {
+            argv: argv,
+            fds: fds,
+            files: files,
+        })
+    }
+
+    // waits for the background processes that are done, keeping their status
+    // for wait.
+    fn reap(&mut self) {
+        let finished = &mut self.finished;
+
+        self.background
+            .retain_mut(|job| match job.process.try_wait() {
+                Some(status) => {
+                    finished.insert(job.process.id(), status);
+                    false
+                }
+                None => true,
+            });
+    }
+
+    // set -x: the words of a command, after PS4, before it runs.
+    fn trace(&mut self, words: &[String]) {
+        if self.state.is_set(Setting::Xtrace) == false {
+            return;
+        }
+
+        let prefix = self.state.variable("PS4").unwrap_or("+ ".to_owned());
+
+        let words = words
+            .iter()
+            .map(|word| escape::quote(word))
+            .collect::<Vec<String>>();
+
+        let _ = writeln!(self.io.stderr, "{prefix}{}", words.join(" "));
+    }
+
+    fn report(&mut self, err: &Error) {
+        let _ = match &self.source {
+            Some(source) => writeln!(
+                self.io.stderr,
+                "eash: {}: line {}: {err}",
+                source.name, source.line
+            ),
+            None => writeln!(self.io.stderr, "eash: {err}"),
+        };
+
+        // an unbound variable ends a script, an interactive shell goes on.
+        let is_fatal = matches!(err.kind(), ErrorKind::UnboundVariable);
+
+        if is_fatal && self.is_interactive == false && self.state.exit_code().is_none() {
+            self.state.exit(err.status());
+        }
+    }
+}
+
+impl Shell for Evaluator {
+    fn state(&self) -> &State {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    fn jobs(&self) -> Vec<(usize, libc::pid_t)> {
+        self.background
+            .iter()
+            .map(|job| (job.number, job.process.id()))
+            .collect()
+    }
+
+    fn wait(&mut self, pid: Option<libc::pid_t>) -> Option<i32> {
+        let pid = match pid {
+            Some(pid) => pid,
+            None => {
+                for job in self.background.drain(..) {
+                    job.process.wait();
+                }
+
+                self.finished.clear();
+
+                return Some(0);
+            }
+        };
+
+        if let Some(status) = self.finished.remove(&pid) {
+            return Some(status);
+        }
+
+        let i = self
+            .background
+            .iter()
+            .position(|job| job.process.id() == pid)?;
+
+        Some(self.background.remove(i).process.wait())
+    }
+
+    fn execute(&mut self, argv: &[String], io: [Fd; 3], lookup: Lookup) -> i32 {
+        let io = Io {
+            stdin: io[0],
+            stdout: io[1],
+            stderr: io[2],
+        };
+
+        let name = match argv.first() {
+            Some(name) => name,
+            None => return 0,
+        };
+
+        let is_function = lookup == Lookup::All && self.state.function(name).is_some();
+
+        if is_function || self.registry.contains(name) {
+            return self.call(argv, io, lookup);
+        }
+
+        let result = match lookup {
+            Lookup::BuiltinsOnly => Err(Error::new(
+                ErrorKind::CommandNotFound,
+                format!("{name}: not a shell builtin"),
+            )),
+            _ => {
+                let fds = BTreeMap::from([
+                    (0, io.stdin.raw()),
+                    (1, io.stdout.raw()),
+                    (2, io.stderr.raw()),
+                ]);
+
+                process::spawn(argv, &fds).map(|process| process.wait())
+            }
+        };
+
+        match result {
+            Ok(status) => status,
+            Err(err) => {
+                let saved_io = std::mem::replace(&mut self.io, io);
+
+                self.report(&err);
+
+                self.io = saved_io;
+
+                err.status()
+            }
+        }
+    }
+
+    fn source(&mut self, name: &str, source: &str, args: Option<Vec<String>>, io: [Fd; 3]) -> i32 {
+        let mut parser = Parser::new(Lexer::new(source));
+
+        parser.set_aliases(self.state.aliases().clone());
+
+        let (block, errors) = parser.parse();
+
+        if errors.is_empty() == false {
+            report_syntax(io[2], name, source, &errors);
+
+            return 2;
+        }
+
+        let io = Io {
+            stdin: io[0],
+            stdout: io[1],
+            stderr: io[2],
+        };
+
+        let saved_io = std::mem::replace(&mut self.io, io);
+
+        let saved_source = self.source.replace(Source {
+            name: name.to_owned(),
+            line: 0,
+        });
+
+        let saved_args = args.map(|args| self.state.set_args(args));
+
+        // a return in the file ends it, like the end of a function.
+        let result = self.eval_source(block, source);
+
+        if let Some(args) = saved_args {
+            self.state.set_args(args);
+        }
+
+        self.source = saved_source;
+
+        self.io = saved_io;
+
+        match result {
+            Ok(_) => self.state.exit_code().unwrap_or(self.state.status()),
+            Err(errors) => {
+                report_syntax(io.stderr, name, source, &errors);
+
+                2
+            }
+        }
+    }
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self::new(State::default(), Registry::default())
+    }
+}
+
+// with noclobber a write fails on an existing regular file, devices such as
+// /dev/null can still be written.
+fn open(kind: RedirectKind, path: &str, is_noclobber: bool) -> Result<File> {
+    let is_regular = fs::metadata(path).is_ok_and(|metadata| metadata.is_file());
+
+    if is_noclobber && is_regular && kind == RedirectKind::Write {
+        return Err(Error::new(
+            ErrorKind::Redirect,
+            format!("{path}: cannot overwrite existing file"),
+        ));
+    }
+
+    let result = match kind {
+        RedirectKind::Write => OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path),
+        RedirectKind::Read => File::open(path),
+    };
+
+    result.map_err(|err| Error::new(ErrorKind::Redirect, format!("{path}: {err}")))
+}
+
+// prints syntax errors of source the way the binary does.
+fn report_syntax(mut stderr: Fd, name: &str, source: &str, errors: &[ParseError]) {
+    let color = stderr.is_terminal();
+
+    for err in errors {
+        let _ = write!(
+            stderr,
+            "{}",
+            Diagnostic::from(err).render(name, source, color)
+        );
+    }
+}
+
+// makes the redirections of a command those of the shell. returns the fds they
+// replaced, for restore.
+fn redirect(prepared: &mut Prepared, io: Io) -> Result<Vec<(RawFd, Option<OwnedFd>)>> {
+    let io = [io.stdin.raw(), io.stdout.raw(), io.stderr.raw()];
+
+    let mut saved = Vec::new();
+
+    for (target, source) in &prepared.fds {
+        let target = *target as RawFd;
+
+        let is_inherited = io.get(target as usize) == Some(source);
+
+        if is_inherited {
+            continue;
+        }
+
+        if *source == target {
+            if let Some(i) = prepared
+                .files
+                .iter()
+                .position(|file| file.as_raw_fd() == *source)
+            {
+                let fd = prepared.files.remove(i).into_raw_fd();
+
+                unsafe { libc::fcntl(fd, libc::F_SETFD, 0) };
+
+                saved.push((target, None));
+            }
+
+            continue;
+        }
+
+        // a copy of what target was, none when it was not open.
+        let copy = unsafe { libc::fcntl(target, libc::F_DUPFD_CLOEXEC, 10) };
+
+        saved.push((
+            target,
+            (copy >= 0).then(|| unsafe { OwnedFd::from_raw_fd(copy) }),
+        ));
+
+        if unsafe { libc::dup2(*source, target) } < 0 {
+            let err = std::io::Error::last_os_error();
+
+            restore(saved);
+
+            return Err(process::os_error(&target.to_string(), err));
+        }
+    }
+
+    Ok(saved)
+}
+
+// puts back the fds redirect replaced, closing those that were not open.
+fn restore(saved: Vec<(RawFd, Option<OwnedFd>)>) {
+    for (target, fd) in saved.into_iter().rev() {
+        match fd {
+            Some(fd) => unsafe { libc::dup2(fd.as_raw_fd(), target) },
+            None => unsafe { libc::close(target) },
+        };
+    }
+}
+
+// whether fd was opened by exec for the shell. the fds the shell opens for
+// itself are all closed on exec.
+fn is_exec_fd(fd: RawFd) -> bool {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+
+    flags >= 0 && flags & libc::FD_CLOEXEC == 0
+}
+
+// the commands of a pipeline, left to right.
+fn flatten<'a>(expr: &'a Expression, commands: &mut Vec<&'a Command>) {
+    match expr {
+        Expression::Command(command) => commands.push(command),
+        Expression::Pipe(pipe) => {
+            if let Some(left) = pipe.left() {
+                flatten(left, commands);
+            }
+
+            if let Some(right) = pipe.right() {
+                flatten(right, commands);
+            }
+        }
+        _ => {}
+    }
+}
@@ -1,2 +1,8 @@
 pub mod error;
 pub mod evaluator;
+
+mod eval;
+mod process;
+mod time;
+
+pub use evaluator::Evaluator;
@@ -0,0 +1,182 @@
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::error::Result;
+use std::collections::BTreeMap;
+use std::io;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+use std::os::unix::process::CommandExt;
+use std::os::unix::process::ExitStatusExt;
+use std::process::Child;
+use std::process::ExitStatus;
+use std::process::Stdio;
+
+// a process started for a command, either a program or a fork of the shell
+// running a builtin or function.
+pub enum Process {
+    Child(Child),
+    Fork(libc::pid_t),
+}
+
+impl Process {
+    pub fn wait(self) -> i32 {
+        match self {
+            Process::Child(mut child) => match child.wait() {
+                Ok(status) => exit_status(status),
+                Err(_) => 1,
+            },
+            Process::Fork(pid) => wait_pid(pid, 0).unwrap_or(1),
+        }
+    }
+
+    pub fn id(&self) -> libc::pid_t {
+        match self {
+            Process::Child(child) => child.id() as libc::pid_t,
+            Process::Fork(pid) => *pid,
+        }
+    }
+
+    pub fn signal(&self, signal: libc::c_int) {
+        unsafe { libc::kill(self.id(), signal) };
+    }
+
+    // the status once the process exited, without blocking.
+    pub fn try_wait(&mut self) -> Option<i32> {
+        match self {
+            Process::Child(child) => match child.try_wait() {
+                Ok(Some(status)) => Some(exit_status(status)),
+                Ok(None) => None,
+                Err(_) => Some(1),
+            },
+            Process::Fork(pid) => wait_pid(*pid, libc::WNOHANG),
+        }
+    }
+}
+
+// 128 plus the signal number for a process killed by a signal, like sh.
+pub fn exit_status(status: ExitStatus) -> i32 {
+    match (status.code(), status.signal()) {
+        (Some(code), _) => code,
+        (None, Some(signal)) => 128 + signal,
+        (None, None) => 1,
+    }
+}
+
+fn wait_pid(pid: libc::pid_t, options: libc::c_int) -> Option<i32> {
+    let mut status = 0;
+
+    loop {
+        let result = unsafe { libc::waitpid(pid, &mut status, options) };
+
+        if result == 0 {
+            return None;
+        }
+
+        if result < 0 {
+            if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+
+            return Some(1);
+        }
+
+        if libc::WIFEXITED(status) {
+            return Some(libc::WEXITSTATUS(status));
+        }
+
+        if libc::WIFSIGNALED(status) {
+            return Some(128 + libc::WTERMSIG(status));
+        }
+    }
+}
+
+// a pipe as (read, write), both closed on exec.
+pub fn pipe() -> Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } < 0 {
+        return Err(os_error("pipe", io::Error::last_os_error()));
+    }
+
+    Ok(unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
+}
+
+// forks the shell, putting caught signals back to their default in the child.
+// returns the pid in the parent and None in the child.
+pub fn fork() -> Result<Option<libc::pid_t>> {
+    match unsafe { libc::fork() } {
+        -1 => Err(os_error("fork", io::Error::last_os_error())),
+        0 => {
+            // the fork runs a command, a signal should end it like any other.
+            es_builtin::signal::reset();
+
+            Ok(None)
+        }
+        pid => Ok(Some(pid)),
+    }
+}
+
+// starts a program with fds set up as mapped: fd number in the child to fd in
+// the shell.
+pub fn spawn(argv: &[String], fds: &BTreeMap<u32, RawFd>) -> Result<Process> {
+    let mut command = std::process::Command::new(&argv[0]);
+
+    command.args(&argv[1..]);
+
+    command.stdin(stdio(fds, 0)?);
+    command.stdout(stdio(fds, 1)?);
+    command.stderr(stdio(fds, 2)?);
+
+    let extra = fds
+        .iter()
+        .filter(|(target, _)| **target > 2)
+        .map(|(target, source)| (*target as RawFd, *source))
+        .collect::<Vec<(RawFd, RawFd)>>();
+
+    if extra.is_empty() == false {
+        unsafe {
+            command.pre_exec(move || {
+                for (target, source) in &extra {
+                    if libc::dup2(*source, *target) < 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+
+                Ok(())
+            });
+        }
+    }
+
+    match command.spawn() {
+        Ok(child) => Ok(Process::Child(child)),
+        Err(err) => Err(match err.kind() {
+            io::ErrorKind::NotFound => Error::new(
+                ErrorKind::CommandNotFound,
+                format!("{}: command not found", argv[0]),
+            ),
+            io::ErrorKind::PermissionDenied => Error::new(
+                ErrorKind::PermissionDenied,
+                format!("{}: permission denied", argv[0]),
+            ),
+            _ => os_error(&argv[0], err),
+        }),
+    }
+}
+
+// a duplicate of the mapped fd, the child closes its own copy.
+fn stdio(fds: &BTreeMap<u32, RawFd>, target: u32) -> Result<Stdio> {
+    let fd = match fds.get(&target) {
+        Some(fd) => *fd,
+        None => return Ok(Stdio::null()),
+    };
+
+    match unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 3) } {
+        -1 => Err(os_error(&format!("&{target}"), io::Error::last_os_error())),
+        fd => Ok(Stdio::from(unsafe { OwnedFd::from_raw_fd(fd) })),
+    }
+}
+
+pub fn os_error(name: &str, err: io::Error) -> Error {
+    Error::new(ErrorKind::ExecutionFailedg, format!("{name}: {err}"))
+}
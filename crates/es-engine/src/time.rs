@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+// the format of bash when TIMEFORMAT is not set.
+pub const DEFAULT_FORMAT: &str = "\nreal\t%3lR\nuser\t%3lU\nsys\t%3lS";
+
+// cpu time of the shell and of the children it waited for, as (user, sys).
+pub fn usage() -> (Duration, Duration) {
+    let mut user = Duration::ZERO;
+
+    let mut sys = Duration::ZERO;
+
+    for who in [libc::RUSAGE_SELF, libc::RUSAGE_CHILDREN] {
+        let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+
+        if unsafe { libc::getrusage(who, &mut usage) } == 0 {
+            user += duration(usage.ru_utime);
+            sys += duration(usage.ru_stime);
+        }
+    }
+
+    (user, sys)
+}
+
+fn duration(time: libc::timeval) -> Duration {
+    Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+}
+
+// expands TIMEFORMAT: %R, %U and %S are the real, user and sys seconds, with
+// an optional precision of 0 to 3 digits and l for the 1m2.345s form. %P is
+// the cpu percentage and %% a percent sign.
+pub fn format(format: &str, real: Duration, user: Duration, sys: Duration) -> String {
+    let mut output = String::new();
+
+    let mut chars = format.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            output.push(ch);
+            continue;
+        }
+
+        let mut precision = 3;
+
+        if let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(10)) {
+            precision = digit.min(3) as usize;
+            chars.next();
+        }
+
+        let is_long = chars.next_if_eq(&'l').is_some();
+
+        let seconds = match chars.next() {
+            Some('R') => real,
+            Some('U') => user,
+            Some('S') => sys,
+            Some('P') => {
+                let cpu = (user + sys).as_secs_f64();
+
+                let percent = match real.is_zero() {
+                    true => 0.0,
+                    false => cpu * 100.0 / real.as_secs_f64(),
+                };
+
+                output.push_str(&format!("{percent:.2}"));
+                continue;
+            }
+            Some('%') => {
+                output.push('%');
+                continue;
+            }
+            Some(ch) => {
+                output.push('%');
+                output.push(ch);
+                continue;
+            }
+            None => {
+                output.push('%');
+                break;
+            }
+        };
+
+        output.push_str(&seconds_string(seconds, precision, is_long));
+    }
+
+    output
+}
+
+fn seconds_string(duration: Duration, precision: usize, is_long: bool) -> String {
+    let seconds = duration.as_secs_f64();
+
+    match is_long {
+        true => {
+            let minutes = (seconds / 60.0).floor();
+
+            format!("{minutes}m{:.precision$}s", seconds - minutes * 60.0)
+        }
+        false => format!("{seconds:.precision$}"),
+    }
+}
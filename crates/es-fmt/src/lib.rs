@@ -0,0 +1,260 @@
+use es_ast::print::quote_value;
+use es_ast::*;
+use es_parser::error::Error;
+use es_parser::lexer::Lexer;
+use es_parser::token::Token;
+use es_parser::Parser;
+
+const INDENT: &str = "    ";
+
+// formats a whole script. comments and single blank lines between statements are
+// kept, everything else is printed in the canonical layout of es_ast::print.
+// returns the syntax errors instead when the source does not parse.
+pub fn format(source: &str) -> Result<String, Vec<Error>> {
+    let (block, errors) = Parser::new(Lexer::new(source)).parse();
+
+    if errors.is_empty() == false {
+        return Err(errors);
+    }
+
+    let mut formatter = Formatter::new(source);
+
+    for statement in &block {
+        formatter.write_item(statement, 0);
+    }
+
+    formatter.write_comments_before(usize::MAX, 0);
+
+    Ok(formatter.finish())
+}
+
+// true when format would leave the source as it is.
+pub fn is_formatted(source: &str) -> Result<bool, Vec<Error>> {
+    Ok(format(source)? == source)
+}
+
+struct Comment {
+    line: usize,
+    text: String,
+}
+
+struct Formatter {
+    // offset of the first byte of each line.
+    lines: Vec<usize>,
+    comments: Vec<Comment>,
+    next_comment: usize,
+    // source line of the last statement or comment written.
+    last_line: usize,
+    is_block_start: bool,
+    output: String,
+}
+
+impl Formatter {
+    fn new(source: &str) -> Self {
+        let mut lines = vec![0];
+
+        for (offset, ch) in source.char_indices() {
+            if ch == '\n' {
+                lines.push(offset + 1);
+            }
+        }
+
+        let mut comments = Vec::new();
+
+        let mut lexer = Lexer::with_comments(source);
+
+        while let Some((token, span)) = lexer.next_with_span() {
+            if let Token::Comment(text) = token {
+                comments.push(Comment {
+                    line: span.line(),
+                    text: text.trim_end().to_owned(),
+                });
+            }
+        }
+
+        Self {
+            lines: lines,
+            comments: comments,
+            next_comment: 0,
+            last_line: 0,
+            is_block_start: true,
+            output: String::new(),
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if self.output.is_empty() == false && self.output.ends_with('\n') == false {
+            self.output.push('\n');
+        }
+
+        self.output
+    }
+
+    fn line_of(&self, offset: usize) -> usize {
+        match self.lines.binary_search(&offset) {
+            Ok(line) => line + 1,
+            Err(line) => line,
+        }
+    }
+
+    fn write_indent(&mut self, indent: usize) {
+        self.output.push_str(&INDENT.repeat(indent));
+    }
+
+    // keeps a single blank line where the source had one or more.
+    fn write_separator(&mut self, line: usize) {
+        if self.is_block_start == false && line > self.last_line + 1 {
+            self.output.push('\n');
+        }
+
+        self.is_block_start = false;
+    }
+
+    fn write_comments_before(&mut self, line: usize, indent: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.line >= line {
+                break;
+            }
+
+            let (line, text) = (comment.line, format!("#{}", comment.text));
+
+            self.next_comment += 1;
+
+            self.write_separator(line);
+            self.write_indent(indent);
+            self.output.push_str(&text);
+            self.output.push('\n');
+
+            self.last_line = line;
+        }
+    }
+
+    fn write_trailing_comment(&mut self, line: usize) {
+        if let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.line == line {
+                let text = format!(" #{}", comment.text);
+
+                self.next_comment += 1;
+
+                self.output.push_str(&text);
+            }
+        }
+    }
+
+    // a statement on its own line, with the comments around it.
+    fn write_item(&mut self, statement: &Statement, indent: usize) {
+        let span = statement.span();
+
+        let start = self.line_of(span.start());
+
+        let end = self.line_of(span.end().saturating_sub(1).max(span.start()));
+
+        self.write_comments_before(start, indent);
+
+        self.write_separator(start);
+        self.write_indent(indent);
+        self.write_statement(statement, indent);
+        self.write_trailing_comment(end);
+        self.output.push('\n');
+
+        self.last_line = end;
+    }
+
+    fn write_statement(&mut self, statement: &Statement, indent: usize) {
+        match statement {
+            Statement::If(node) => {
+                self.output.push_str(&format!("if {} ", node.comparison()));
+                self.write_block(node.block(), indent);
+
+                for child in node.children() {
+                    self.output
+                        .push_str(&format!(" elif {} ", child.comparison()));
+                    self.write_block(child.block(), indent);
+                }
+
+                if let Some(block) = node.else_block() {
+                    self.output.push_str(" else ");
+                    self.write_block(block, indent);
+                }
+            }
+
+            Statement::Loop(node) => {
+                self.output
+                    .push_str(&format!("loop {} ", node.comparison()));
+                self.write_block(node.block(), indent);
+            }
+
+            Statement::Def(node) => {
+                let params = node
+                    .params()
+                    .iter()
+                    .map(|param| format!("${param}"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                self.output
+                    .push_str(&format!("def {}({params}) ", quote_value(node.name())));
+                self.write_block(node.block(), indent);
+            }
+
+            Statement::Block(node) => self.write_block(node, indent),
+
+            _ => self.output.push_str(&statement.to_string()),
+        }
+    }
+
+    fn write_block(&mut self, block: &Block, indent: usize) {
+        let span = block.span();
+
+        let open = self.line_of(span.start());
+
+        let close = self.line_of(span.end().saturating_sub(1).max(span.start()));
+
+        let has_comment = matches!(
+            self.comments.get(self.next_comment),
+            Some(comment) if comment.line < close
+        );
+
+        if block.is_empty() && has_comment == false {
+            self.output.push_str("{}");
+            return;
+        }
+
+        self.output.push_str("{\n");
+
+        self.last_line = open;
+
+        self.is_block_start = true;
+
+        for statement in block {
+            self.write_item(statement, indent + 1);
+        }
+
+        self.write_comments_before(close, indent + 1);
+
+        self.write_indent(indent);
+
+        self.output.push('}');
+
+        self.last_line = close;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = "echo a\"b c\"d   \"e f\"\nif $x == 1 {\necho   x ; ls | wc\n}\n";
+
+    #[test]
+    fn keeps_embedded_quotes() {
+        assert_eq!(format("echo a\"b\n").unwrap(), "echo a\"b\n");
+    }
+
+    #[test]
+    fn formatting_twice_is_formatting_once() {
+        let once = format(SCRIPT).unwrap();
+
+        assert_eq!(format(&once).unwrap(), once);
+    }
+}
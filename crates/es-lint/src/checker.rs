@@ -0,0 +1,352 @@
+use crate::Lint;
+use es_ast::visit::*;
+use es_ast::*;
+use es_parser::diagnostic::Diagnostic;
+use std::collections::HashSet;
+
+// special parameters and positional arguments, always set by the shell.
+const SPECIAL_VARIABLES: [&str; 7] = ["?", "#", "@", "*", "$", "!", "-"];
+
+// commands that never read the files named in their arguments.
+const NON_READING_COMMANDS: [&str; 3] = ["echo", "printf", "touch"];
+
+// walks a parsed script and collects the lints it triggers.
+pub struct Checker {
+    lints: Vec<(Lint, Diagnostic)>,
+    // names assigned and read anywhere in the script.
+    assigned: HashSet<String>,
+    used: HashSet<String>,
+    // names assigned so far, in source order.
+    defined: HashSet<String>,
+    reported: HashSet<String>,
+    params: Vec<Vec<String>>,
+    in_pipeline: bool,
+    // innermost span around the node being visited, leaves have none.
+    span: Span,
+}
+
+impl Checker {
+    pub fn check(block: &Block) -> Vec<(Lint, Diagnostic)> {
+        let mut collector = Collector::default();
+
+        collector.visit_block(block);
+
+        let mut checker = Self {
+            lints: Vec::new(),
+            assigned: collector.assigned,
+            used: collector.used,
+            defined: HashSet::new(),
+            reported: HashSet::new(),
+            params: Vec::new(),
+            in_pipeline: false,
+            span: block.span(),
+        };
+
+        checker.visit_block(block);
+
+        checker.lints
+    }
+
+    fn report(&mut self, lint: Lint, span: Span, message: String, label: String) {
+        let diagnostic = Diagnostic::new(lint.severity(), message)
+            .with_code(lint.id())
+            .with_span(span)
+            .with_label(label);
+
+        self.lints.push((lint, diagnostic));
+    }
+
+    fn report_with_note(&mut self, lint: Lint, span: Span, message: String, note: String) {
+        let diagnostic = Diagnostic::new(lint.severity(), message)
+            .with_code(lint.id())
+            .with_span(span)
+            .with_note(note);
+
+        self.lints.push((lint, diagnostic));
+    }
+
+    fn is_defined(&self, name: &str) -> bool {
+        // inside a function any global may have been assigned before the call.
+        self.defined.contains(name)
+            || self.params.iter().flatten().any(|param| param == name)
+            || (self.params.is_empty() == false && self.assigned.contains(name))
+            || is_special(name)
+            || is_environment(name)
+    }
+
+    // files written and read by the commands of one pipeline.
+    fn check_pipeline(&mut self, expr: &Expression) {
+        let mut commands = Vec::new();
+
+        collect_commands(expr, &mut commands);
+
+        let mut reads = Vec::new();
+
+        let mut writes = Vec::new();
+
+        for command in &commands {
+            let is_reading = match command.prefix() {
+                Expression::String(name) => NON_READING_COMMANDS.contains(&name.as_str()) == false,
+                _ => true,
+            };
+
+            for expr in command.suffix() {
+                match expr {
+                    Expression::Redirect(redirect) => {
+                        if let Expression::String(file) = redirect.right() {
+                            match redirect.kind() {
+                                RedirectKind::Write => writes.push((file, redirect.span())),
+                                RedirectKind::Read => reads.push(file),
+                            }
+                        }
+                    }
+
+                    Expression::String(arg) if is_reading && arg.starts_with('-') == false => {
+                        reads.push(arg)
+                    }
+
+                    _ => {}
+                }
+            }
+        }
+
+        for (file, span) in writes {
+            if reads.contains(&file) {
+                self.report_with_note(
+                    Lint::SameFileRedirect,
+                    span,
+                    format!("`{file}` is truncated before it is read"),
+                    "write to a temporary file and move it over the original".to_owned(),
+                );
+            }
+        }
+    }
+}
+
+impl Visitor for Checker {
+    fn visit_statement(&mut self, statement: &Statement) {
+        self.span = statement.span();
+
+        if let Statement::Expression(expr) = statement {
+            if let Some(command) = single_command(expr) {
+                if is_command(command, &["cd"]) {
+                    self.report_with_note(
+                        Lint::UncheckedCd,
+                        statement.span(),
+                        "`cd` can fail and the script goes on in the wrong directory".to_owned(),
+                        "use `cd dir || exit` or `cd dir || return`".to_owned(),
+                    );
+                }
+            }
+        }
+
+        walk_statement(self, statement);
+    }
+
+    fn visit_block(&mut self, node: &Block) {
+        let mut terminator: Option<&str> = None;
+
+        for statement in node {
+            if let Some(keyword) = terminator.take() {
+                self.report(
+                    Lint::UnreachableCode,
+                    statement.span(),
+                    "unreachable statement".to_owned(),
+                    format!("this never runs, it follows `{keyword}`"),
+                );
+            }
+
+            self.visit_statement(statement);
+
+            terminator = terminator_of(statement);
+        }
+    }
+
+    fn visit_def(&mut self, node: &Def) {
+        self.params.push(node.params().to_vec());
+
+        walk_def(self, node);
+
+        self.params.pop();
+    }
+
+    fn visit_assign(&mut self, node: &Assign) {
+        self.span = node.span();
+
+        self.visit_expression(node.expr());
+
+        if let Expression::Variable(name) = node.identify() {
+            if self.used.contains(name) == false && is_environment(name) == false {
+                self.report(
+                    Lint::UnusedAssignment,
+                    node.span(),
+                    format!("`${name}` is assigned but never read"),
+                    "this value is never used".to_owned(),
+                );
+            }
+
+            self.defined.insert(name.clone());
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        let is_pipeline = matches!(expr, Expression::Pipe(_) | Expression::Command(_));
+
+        if is_pipeline == false || self.in_pipeline {
+            return walk_expression(self, expr);
+        }
+
+        self.check_pipeline(expr);
+
+        self.in_pipeline = true;
+
+        walk_expression(self, expr);
+
+        self.in_pipeline = false;
+    }
+
+    fn visit_command(&mut self, node: &Command) {
+        self.span = node.span();
+
+        self.visit_expression(node.prefix());
+
+        for (expr, span) in node.suffix().iter().zip(node.suffix().spans()) {
+            self.span = *span;
+
+            if let Expression::Variable(name) = expr {
+                self.report(
+                    Lint::UnquotedVariable,
+                    *span,
+                    format!("`${name}` is split into words and globbed"),
+                    format!("quote it as \"${name}\" to pass it as one argument"),
+                );
+            }
+
+            self.visit_expression(expr);
+        }
+    }
+
+    fn visit_redirect(&mut self, node: &Redirect) {
+        self.span = node.span();
+
+        walk_redirect(self, node);
+    }
+
+    fn visit_return(&mut self, node: &Return) {
+        self.span = node.span();
+
+        walk_return(self, node);
+    }
+
+    fn visit_comparison(&mut self, node: &Comparison) {
+        self.span = node.span();
+
+        let string = match (node.left(), node.right()) {
+            (Expression::Number(_), Expression::String(string))
+            | (Expression::String(string), Expression::Number(_)) => Some(string),
+            _ => None,
+        };
+
+        if let Some(string) = string {
+            if string.trim().parse::<isize>().is_err() {
+                self.report(
+                    Lint::NumberStringComparison,
+                    node.span(),
+                    "number compared with a string that is not a number".to_owned(),
+                    format!("`{string}` is not a number"),
+                );
+            }
+        }
+
+        walk_comparison(self, node);
+    }
+
+    fn visit_variable(&mut self, name: &str) {
+        if self.is_defined(name) || self.reported.contains(name) {
+            return;
+        }
+
+        self.reported.insert(name.to_owned());
+
+        self.report(
+            Lint::UndefinedVariable,
+            self.span,
+            format!("`${name}` is used before it is assigned"),
+            format!("`${name}` is not assigned before this"),
+        );
+    }
+}
+
+// names assigned and read anywhere in the script.
+#[derive(Default)]
+struct Collector {
+    assigned: HashSet<String>,
+    used: HashSet<String>,
+}
+
+impl Visitor for Collector {
+    fn visit_assign(&mut self, node: &Assign) {
+        if let Expression::Variable(name) = node.identify() {
+            self.assigned.insert(name.clone());
+        }
+
+        self.visit_expression(node.expr());
+    }
+
+    fn visit_variable(&mut self, name: &str) {
+        self.used.insert(name.to_owned());
+    }
+}
+
+fn is_special(name: &str) -> bool {
+    SPECIAL_VARIABLES.contains(&name) || name.chars().all(|ch| ch.is_ascii_digit())
+}
+
+// upper case names are taken to come from the environment.
+fn is_environment(name: &str) -> bool {
+    name.chars().any(|ch| ch.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit() || ch == '_')
+}
+
+fn is_command(command: &Command, names: &[&str]) -> bool {
+    matches!(command.prefix(), Expression::String(name) if names.contains(&name.as_str()))
+}
+
+// the command of an expression that is neither a pipeline nor a list.
+fn single_command(expr: &Expression) -> Option<&Command> {
+    match expr {
+        Expression::Command(command) => Some(command),
+        _ => None,
+    }
+}
+
+fn collect_commands<'a>(expr: &'a Expression, commands: &mut Vec<&'a Command>) {
+    match expr {
+        Expression::Command(command) => commands.push(command),
+        Expression::Pipe(pipe) => {
+            if let Some(left) = pipe.left() {
+                collect_commands(left, commands);
+            }
+
+            if let Some(right) = pipe.right() {
+                collect_commands(right, commands);
+            }
+        }
+        _ => {}
+    }
+}
+
+// the keyword after which the rest of the block never runs.
+fn terminator_of(statement: &Statement) -> Option<&'static str> {
+    match statement {
+        Statement::Return(_) => Some("return"),
+        Statement::Expression(expr) => match single_command(expr) {
+            Some(command) if is_command(command, &["exit"]) => Some("exit"),
+            Some(command) if is_command(command, &["return"]) => Some("return"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,161 @@
+pub mod checker;
+
+use crate::checker::Checker;
+use es_parser::diagnostic::Diagnostic;
+use es_parser::diagnostic::Severity;
+use es_parser::lexer::Lexer;
+use es_parser::token::Token;
+use es_parser::Parser;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+// a comment `# eash-lint: allow(id, ...)` silences the listed lints on the line
+// it ends, or on the next line when it stands on a line of its own. `all`
+// silences every lint.
+const ALLOW_PREFIX: &str = "eash-lint:";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Lint {
+    UndefinedVariable,
+    UnquotedVariable,
+    UnreachableCode,
+    NumberStringComparison,
+    UncheckedCd,
+    SameFileRedirect,
+    UnusedAssignment,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 7] = [
+        Lint::UndefinedVariable,
+        Lint::UnquotedVariable,
+        Lint::UnreachableCode,
+        Lint::NumberStringComparison,
+        Lint::UncheckedCd,
+        Lint::SameFileRedirect,
+        Lint::UnusedAssignment,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Lint::UndefinedVariable => "undefined-variable",
+            Lint::UnquotedVariable => "unquoted-variable",
+            Lint::UnreachableCode => "unreachable-code",
+            Lint::NumberStringComparison => "number-string-comparison",
+            Lint::UncheckedCd => "unchecked-cd",
+            Lint::SameFileRedirect => "same-file-redirect",
+            Lint::UnusedAssignment => "unused-assignment",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.id() == id)
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Lint::SameFileRedirect => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Lint::UndefinedVariable => "variable used before it is assigned",
+            Lint::UnquotedVariable => "unquoted variable is split into words and globbed",
+            Lint::UnreachableCode => "statement after `return` or `exit` never runs",
+            Lint::NumberStringComparison => "number compared with a non-numeric string",
+            Lint::UncheckedCd => "`cd` whose failure is not handled",
+            Lint::SameFileRedirect => "output redirected to a file the pipeline reads",
+            Lint::UnusedAssignment => "variable assigned but never read",
+        }
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+// lints a whole script. syntax errors are returned instead of lints when the
+// source does not parse, a broken tree would only produce noise.
+pub fn lint(source: &str) -> Vec<Diagnostic> {
+    lint_with(source, &Lint::ALL)
+}
+
+// like lint, reporting only the given lints.
+pub fn lint_with(source: &str, lints: &[Lint]) -> Vec<Diagnostic> {
+    let (block, errors) = Parser::new(Lexer::new(source)).parse();
+
+    if errors.is_empty() == false {
+        return errors.iter().map(Diagnostic::from).collect();
+    }
+
+    let allowed = allowed_lints(source);
+
+    let mut diagnostics = Checker::check(&block)
+        .into_iter()
+        .filter(|(lint, diagnostic)| {
+            if lints.contains(lint) == false {
+                return false;
+            }
+
+            let line = diagnostic.span().map(|span| span.line()).unwrap_or(0);
+
+            let is_allowed = |line: usize| {
+                allowed
+                    .get(&line)
+                    .is_some_and(|ids| ids.iter().any(|id| id == "all" || id == lint.id()))
+            };
+
+            is_allowed(line) == false
+        })
+        .map(|(_, diagnostic)| diagnostic)
+        .collect::<Vec<Diagnostic>>();
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span().map(|span| span.start()));
+
+    diagnostics
+}
+
+// lint ids allowed by the comments of each line.
+fn allowed_lints(source: &str) -> HashMap<usize, Vec<String>> {
+    let mut allowed = HashMap::new();
+
+    let mut lexer = Lexer::with_comments(source);
+
+    // line of the last token that was not a comment.
+    let mut last_line = 0;
+
+    while let Some((token, span)) = lexer.next_with_span() {
+        let Token::Comment(text) = token else {
+            if token != Token::EOL {
+                last_line = span.line();
+            }
+            continue;
+        };
+
+        let line = match span.line() == last_line {
+            true => span.line(),
+            false => span.line() + 1,
+        };
+
+        let ids = text
+            .trim()
+            .strip_prefix(ALLOW_PREFIX)
+            .map(|rest| rest.trim())
+            .and_then(|rest| rest.strip_prefix("allow("))
+            .and_then(|rest| rest.strip_suffix(')'));
+
+        if let Some(ids) = ids {
+            allowed
+                .entry(line)
+                .or_insert_with(Vec::new)
+                .extend(ids.split(',').map(|id| id.trim().to_owned()));
+        }
+    }
+
+    allowed
+}
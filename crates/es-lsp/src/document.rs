@@ -0,0 +1,181 @@
+use es_ast::visit::*;
+use es_ast::*;
+use es_parser::lexer::Lexer;
+use es_parser::token::Token;
+use es_parser::Parser;
+
+// an open text document. lsp positions count lines from 0 and columns in utf-16
+// code units, spans use byte offsets.
+pub struct Document {
+    text: String,
+    // offset of the first byte of each line.
+    lines: Vec<usize>,
+}
+
+// a `def` or an assignment found in the document.
+pub struct Definition {
+    pub name: String,
+    pub span: Span,
+    pub params: Vec<String>,
+    pub is_function: bool,
+}
+
+impl Document {
+    pub fn new(text: String) -> Self {
+        let mut lines = vec![0];
+
+        for (offset, ch) in text.char_indices() {
+            if ch == '\n' {
+                lines.push(offset + 1);
+            }
+        }
+
+        Self {
+            text: text,
+            lines: lines,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.text.len());
+
+        let line = match self.lines.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+
+        let column = self.text[self.lines[line]..offset].encode_utf16().count();
+
+        (line, column)
+    }
+
+    pub fn offset(&self, line: usize, column: usize) -> usize {
+        let start = match self.lines.get(line) {
+            Some(start) => *start,
+            None => return self.text.len(),
+        };
+
+        let mut units = 0;
+
+        for (offset, ch) in self.text[start..].char_indices() {
+            if units >= column || ch == '\n' {
+                return start + offset;
+            }
+
+            units += ch.len_utf16();
+        }
+
+        self.text.len()
+    }
+
+    pub fn utf16_len(&self, span: &Span) -> usize {
+        self.text[span.start()..span.end()].encode_utf16().count()
+    }
+
+    pub fn tokens(&self, comments: bool) -> Vec<(Token, Span)> {
+        let mut lexer = match comments {
+            true => Lexer::with_comments(&self.text),
+            false => Lexer::new(&self.text),
+        };
+
+        let mut tokens = Vec::new();
+
+        while let Some((token, span)) = lexer.next_with_span() {
+            if token == Token::EOF {
+                break;
+            }
+
+            tokens.push((token, span));
+        }
+
+        tokens
+    }
+
+    // the token under the offset, the cursor may also sit right after it.
+    pub fn token_at(&self, offset: usize) -> Option<(Token, Span)> {
+        self.tokens(false)
+            .into_iter()
+            .filter(|(token, _)| matches!(token, Token::String(_) | Token::Ident(_)))
+            .find(|(_, span)| span.start() <= offset && offset <= span.end())
+    }
+
+    // functions and variables in source order, with the parameters of the
+    // function the offset is in first.
+    pub fn definitions(&self, offset: usize) -> Vec<Definition> {
+        let (block, _) = Parser::new(Lexer::new(&self.text)).parse();
+
+        let mut collector = Collector {
+            offset: offset,
+            definitions: Vec::new(),
+            params: Vec::new(),
+        };
+
+        collector.visit_block(&block);
+
+        collector.params.append(&mut collector.definitions);
+
+        collector.params
+    }
+
+    // functions and the first assignment of each variable, for a name.
+    pub fn definition(&self, name: &str, is_function: bool, offset: usize) -> Option<Definition> {
+        self.definitions(offset)
+            .into_iter()
+            .find(|definition| definition.name == name && definition.is_function == is_function)
+    }
+}
+
+struct Collector {
+    offset: usize,
+    definitions: Vec<Definition>,
+    // parameters of the def around the offset.
+    params: Vec<Definition>,
+}
+
+impl Visitor for Collector {
+    fn visit_def(&mut self, node: &Def) {
+        self.definitions.push(Definition {
+            name: node.name().to_owned(),
+            span: node.span(),
+            params: node.params().to_vec(),
+            is_function: true,
+        });
+
+        if node.span().start() <= self.offset && self.offset <= node.span().end() {
+            for param in node.params() {
+                self.params.push(Definition {
+                    name: param.clone(),
+                    span: node.span(),
+                    params: Vec::new(),
+                    is_function: false,
+                });
+            }
+        }
+
+        walk_def(self, node);
+    }
+
+    fn visit_assign(&mut self, node: &Assign) {
+        if let Expression::Variable(name) = node.identify() {
+            let is_defined = self
+                .definitions
+                .iter()
+                .any(|definition| &definition.name == name && definition.is_function == false);
+
+            if is_defined == false {
+                self.definitions.push(Definition {
+                    name: name.clone(),
+                    span: node.span(),
+                    params: Vec::new(),
+                    is_function: false,
+                });
+            }
+        }
+
+        walk_assign(self, node);
+    }
+}
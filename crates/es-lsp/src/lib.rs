@@ -0,0 +1,442 @@
+pub mod document;
+pub mod rpc;
+pub mod semantic;
+
+use crate::document::Document;
+use es_ast::Span;
+use es_builtin::Registry;
+use es_parser::diagnostic::Diagnostic;
+use es_parser::diagnostic::Severity;
+use es_parser::token::Token;
+use serde_json::json;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+const METHOD_NOT_FOUND: i64 = -32601;
+
+const INVALID_PARAMS: i64 = -32602;
+
+// completion item kinds of the protocol.
+const FUNCTION_KIND: u32 = 3;
+
+const VARIABLE_KIND: u32 = 6;
+
+// there is no kind for options, properties come closest.
+const OPTION_KIND: u32 = 10;
+
+// serves one client until it sends `exit` or closes the input. returns the exit
+// status the protocol asks for: 0 after a shutdown request, 1 otherwise.
+pub fn run<R: BufRead, W: Write>(mut input: R, output: W) -> io::Result<i32> {
+    let mut server = Server::new(output);
+
+    while let Some(message) = rpc::read_message(&mut input)? {
+        if let Some(status) = server.handle(message)? {
+            return Ok(status);
+        }
+    }
+
+    Ok(1)
+}
+
+pub struct Server<W: Write> {
+    output: W,
+    documents: HashMap<String, Document>,
+    registry: Registry,
+    is_shutdown: bool,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output: output,
+            documents: HashMap::new(),
+            registry: Registry::default(),
+            is_shutdown: false,
+        }
+    }
+
+    // handles a request or notification, returns the exit status once the
+    // client asked the server to exit.
+    pub fn handle(&mut self, message: Value) -> io::Result<Option<i32>> {
+        let method = match message["method"].as_str() {
+            Some(method) => method.to_owned(),
+            // responses to requests of the server, none are sent.
+            None => return Ok(None),
+        };
+
+        let params = &message["params"];
+
+        let id = match message.get("id") {
+            Some(id) => id.clone(),
+            None => {
+                if method == "exit" {
+                    return Ok(Some(match self.is_shutdown {
+                        true => 0,
+                        false => 1,
+                    }));
+                }
+
+                self.notify(&method, params)?;
+
+                return Ok(None);
+            }
+        };
+
+        let response = match self.request(&method, params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        };
+
+        rpc::write_message(&mut self.output, &response)?;
+
+        Ok(None)
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "completionProvider": { "triggerCharacters": ["$"] },
+                    "documentFormattingProvider": true,
+                    "semanticTokensProvider": {
+                        "legend": { "tokenTypes": semantic::TOKEN_TYPES, "tokenModifiers": [] },
+                        "full": true,
+                    },
+                },
+                "serverInfo": { "name": "eash-lsp", "version": env!("CARGO_PKG_VERSION") },
+            })),
+
+            "shutdown" => {
+                self.is_shutdown = true;
+                Ok(Value::Null)
+            }
+
+            "textDocument/hover" => self.hover(params),
+            "textDocument/definition" => self.definition(params),
+            "textDocument/completion" => self.completion(params),
+            "textDocument/formatting" => self.formatting(params),
+
+            "textDocument/semanticTokens/full" => {
+                let (_, document) = self.document(params)?;
+
+                Ok(json!({ "data": semantic::tokens(document) }))
+            }
+
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method `{method}`"))),
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: &Value) -> io::Result<()> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        let text = match method {
+            "textDocument/didOpen" => params["textDocument"]["text"].as_str(),
+            // only full document sync is announced, the last change holds the text.
+            "textDocument/didChange" => params["contentChanges"]
+                .as_array()
+                .and_then(|changes| changes.last())
+                .and_then(|change| change["text"].as_str()),
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+
+                return self.publish(uri, Vec::new());
+            }
+            _ => return Ok(()),
+        };
+
+        if let Some(text) = text {
+            let document = Document::new(text.to_owned());
+
+            let diagnostics = es_lint::lint(document.text())
+                .iter()
+                .map(|diagnostic| to_lsp_diagnostic(&document, diagnostic))
+                .collect();
+
+            self.documents.insert(uri.to_owned(), document);
+
+            self.publish(uri, diagnostics)?;
+        }
+
+        Ok(())
+    }
+
+    fn publish(&mut self, uri: &str, diagnostics: Vec<Value>) -> io::Result<()> {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        });
+
+        rpc::write_message(&mut self.output, &notification)
+    }
+
+    fn document(&self, params: &Value) -> Result<(&str, &Document), (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        match self.documents.get_key_value(uri) {
+            Some((uri, document)) => Ok((uri, document)),
+            None => Err((INVALID_PARAMS, format!("unknown document `{uri}`"))),
+        }
+    }
+
+    // the document of the params and the byte offset of its position.
+    fn cursor(&self, params: &Value) -> Result<(&str, &Document, usize), (i64, String)> {
+        let (uri, document) = self.document(params)?;
+
+        let line = params["position"]["line"].as_u64().unwrap_or_default() as usize;
+
+        let column = params["position"]["character"].as_u64().unwrap_or_default() as usize;
+
+        Ok((uri, document, document.offset(line, column)))
+    }
+
+    fn hover(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_, document, offset) = self.cursor(params)?;
+
+        let (token, span) = match document.token_at(offset) {
+            Some(found) => found,
+            None => return Ok(Value::Null),
+        };
+
+        let contents = match &token {
+            Token::String(name) => match document.definition(name, true, offset) {
+                Some(definition) => Some(format!(
+                    "```eash\ndef {name}({})\n```",
+                    definition
+                        .params
+                        .iter()
+                        .map(|param| format!("${param}"))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )),
+                None => self.registry.get(name).map(|builtin| {
+                    let mut contents = format!(
+                        "```\n{}\n```\n{}",
+                        builtin.synopsis(),
+                        builtin.description()
+                    );
+
+                    if builtin.options().is_empty() == false {
+                        contents.push('\n');
+                    }
+
+                    for (option, description) in builtin.options() {
+                        contents.push_str(&format!("\n- `{option}` {description}"));
+                    }
+
+                    contents
+                }),
+            },
+            _ => None,
+        };
+
+        Ok(match contents {
+            Some(contents) => json!({
+                "contents": { "kind": "markdown", "value": contents },
+                "range": to_range(document, &span),
+            }),
+            None => Value::Null,
+        })
+    }
+
+    fn definition(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (uri, document, offset) = self.cursor(params)?;
+
+        let definition = match document.token_at(offset) {
+            Some((Token::String(name), _)) => document.definition(&name, true, offset),
+            Some((Token::Ident(name), _)) => document.definition(&name, false, offset),
+            _ => None,
+        };
+
+        Ok(match definition {
+            Some(definition) => {
+                json!({ "uri": uri, "range": to_range(document, &definition.span) })
+            }
+            None => Value::Null,
+        })
+    }
+
+    fn completion(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_, document, offset) = self.cursor(params)?;
+
+        // the word before the cursor is replaced by the completion.
+        let start = es_parser::word_start(&document.text()[..offset]);
+
+        let range = to_range(document, &Span::new(start, offset, 0, 0));
+
+        let item = |label: String, kind: u32, detail: &str| {
+            json!({
+                "label": label,
+                "kind": kind,
+                "detail": detail,
+                "textEdit": { "range": range, "newText": label },
+            })
+        };
+
+        let mut items = Vec::new();
+
+        // an option of the builtin the command starts with.
+        if document.text()[start..offset].starts_with('-') {
+            let command = es_parser::command_start(&document.text()[..start]);
+
+            let name = document.text()[command..start]
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+
+            if let Some(builtin) = self.registry.get(name) {
+                for (option, description) in builtin.options() {
+                    let flag = option.split(' ').next().unwrap_or(option);
+
+                    items.push(item(flag.to_owned(), OPTION_KIND, description));
+                }
+
+                if builtin.has_help() {
+                    items.push(item(
+                        "--help".to_owned(),
+                        OPTION_KIND,
+                        "describe the builtin",
+                    ));
+                }
+
+                return Ok(json!(items));
+            }
+        }
+
+        let mut names = Vec::new();
+
+        for definition in document.definitions(offset) {
+            let label = match definition.is_function {
+                true => definition.name,
+                false => format!("${}", definition.name),
+            };
+
+            if names.contains(&label) {
+                continue;
+            }
+
+            items.push(match definition.is_function {
+                true => item(label.clone(), FUNCTION_KIND, "function"),
+                false => item(label.clone(), VARIABLE_KIND, "variable"),
+            });
+
+            names.push(label);
+        }
+
+        for builtin in self.registry.iter() {
+            items.push(item(
+                builtin.name().to_owned(),
+                FUNCTION_KIND,
+                builtin.synopsis(),
+            ));
+        }
+
+        Ok(json!(items))
+    }
+
+    fn formatting(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_, document) = self.document(params)?;
+
+        // a document with syntax errors is left as it is, the errors are
+        // already published as diagnostics.
+        let formatted = match es_fmt::format(document.text()) {
+            Ok(formatted) if formatted != document.text() => formatted,
+            _ => return Ok(json!([])),
+        };
+
+        let whole = Span::new(0, document.text().len(), 0, 0);
+
+        Ok(json!([{ "range": to_range(document, &whole), "newText": formatted }]))
+    }
+}
+
+fn to_range(document: &Document, span: &Span) -> Value {
+    let (start_line, start_column) = document.position(span.start());
+
+    let (end_line, end_column) = document.position(span.end());
+
+    json!({
+        "start": { "line": start_line, "character": start_column },
+        "end": { "line": end_line, "character": end_column },
+    })
+}
+
+fn to_lsp_diagnostic(document: &Document, diagnostic: &Diagnostic) -> Value {
+    let span = diagnostic.span().unwrap_or_default();
+
+    let severity = match diagnostic.severity() {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Note => 3,
+    };
+
+    let mut message = diagnostic.message().to_owned();
+
+    for note in diagnostic
+        .label()
+        .into_iter()
+        .chain(diagnostic.notes().iter().map(|note| note.as_str()))
+    {
+        message.push('\n');
+        message.push_str(note);
+    }
+
+    json!({
+        "range": to_range(document, &span),
+        "severity": severity,
+        "code": diagnostic.code(),
+        "source": "eash",
+        "message": message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_after_a_multibyte_delimiter() {
+        let mut server = Server::new(Vec::new());
+
+        let uri = "file:///test.es";
+
+        server
+            .handle(json!({
+                "method": "textDocument/didOpen",
+                "params": { "textDocument": { "uri": uri, "text": "echo\u{a0}ec" } },
+            }))
+            .unwrap();
+
+        server.output.clear();
+
+        server
+            .handle(json!({
+                "id": 1,
+                "method": "textDocument/completion",
+                "params": {
+                    "textDocument": { "uri": uri },
+                    "position": { "line": 0, "character": 7 },
+                },
+            }))
+            .unwrap();
+
+        let response = rpc::read_message(&mut server.output.as_slice())
+            .unwrap()
+            .unwrap();
+
+        let items = response["result"].as_array().unwrap();
+
+        assert!(items.iter().any(|item| item["label"] == "echo"));
+        assert_eq!(items[0]["textEdit"]["range"]["start"]["character"], 5);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::document::Document;
+use es_parser::token::Token;
+
+// indexes into this legend are the token types sent to the client.
+pub const TOKEN_TYPES: [&str; 7] = [
+    "keyword", "function", "variable", "number", "string", "comment", "operator",
+];
+
+const KEYWORD: usize = 0;
+const FUNCTION: usize = 1;
+const VARIABLE: usize = 2;
+const NUMBER: usize = 3;
+const STRING: usize = 4;
+const COMMENT: usize = 5;
+const OPERATOR: usize = 6;
+
+// semantic tokens of the whole document, in the relative encoding of the
+// protocol: line delta, start delta, length, type and modifiers.
+pub fn tokens(document: &Document) -> Vec<usize> {
+    let mut data = Vec::new();
+
+    let (mut last_line, mut last_column) = (0, 0);
+
+    // a word is a command name at the start of a statement or pipeline.
+    let mut is_command = true;
+
+    for (token, span) in document.tokens(true) {
+        // the time keyword is a word the parser reads where a command starts.
+        let is_time = is_command && token == Token::String("time".to_owned());
+
+        let kind = match &token {
+            Token::Def
+            | Token::If
+            | Token::Elif
+            | Token::Else
+            | Token::Loop
+            | Token::Return
+            | Token::True
+            | Token::False => Some(KEYWORD),
+            Token::String(_) if is_time => Some(KEYWORD),
+            Token::String(_) if is_command => Some(FUNCTION),
+            Token::String(_) => Some(STRING),
+            Token::Ident(_) => Some(VARIABLE),
+            Token::Number(_) | Token::FD(_) => Some(NUMBER),
+            Token::Comment(_) => Some(COMMENT),
+            Token::Pipe
+            | Token::Assign
+            | Token::Equal
+            | Token::NotEqual
+            | Token::Gt
+            | Token::Lt
+            | Token::OR
+            | Token::AND
+            | Token::Bang
+            | Token::Ampersand => Some(OPERATOR),
+            _ => None,
+        };
+
+        if token != Token::EOL && matches!(token, Token::Comment(_)) == false {
+            is_command = is_time
+                || matches!(
+                    token,
+                    Token::Semicolon
+                        | Token::Pipe
+                        | Token::AND
+                        | Token::OR
+                        | Token::LBrace
+                        | Token::RBrace
+                        | Token::Def
+                );
+        } else if token == Token::EOL {
+            is_command = true;
+        }
+
+        let kind = match kind {
+            Some(kind) => kind,
+            None => continue,
+        };
+
+        let (line, column) = document.position(span.start());
+
+        // multiline tokens are not supported by every client.
+        if document.position(span.end()).0 != line {
+            continue;
+        }
+
+        let delta = match line == last_line {
+            true => column - last_column,
+            false => column,
+        };
+
+        data.extend([line - last_line, delta, document.utf16_len(&span), kind, 0]);
+
+        (last_line, last_column) = (line, column);
+    }
+
+    data
+}
@@ -0,0 +1,231 @@
+use crate::error::Error;
+use es_ast::Span;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+const RESET: &str = "\x1b[0m";
+
+const BOLD: &str = "\x1b[1m";
+
+const RED: &str = "\x1b[1;31m";
+
+const YELLOW: &str = "\x1b[1;33m";
+
+const BLUE: &str = "\x1b[1;34m";
+
+const GREEN: &str = "\x1b[1;32m";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Severity {
+    Note,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Note => GREEN,
+            Severity::Warning => YELLOW,
+            Severity::Error => RED,
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Note => write!(f, "note"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: Option<String>,
+    message: String,
+    span: Option<Span>,
+    label: Option<String>,
+    notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: String) -> Self {
+        Self {
+            severity: severity,
+            code: None,
+            message: message,
+            span: None,
+            label: None,
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(code.to_owned());
+        self
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    // rustc style rendering:
+    //
+    // error[E0001]: unexpected `}`
+    //  --> script.es:1:12
+    //   |
+    // 1 | if $x == 1 }
+    //   |            ^ expected `{`
+    pub fn render(&self, name: &str, source: &str, color: bool) -> String {
+        let paint = |code: &str, text: &str| -> String {
+            match color {
+                true => format!("{code}{text}{RESET}"),
+                false => text.to_owned(),
+            }
+        };
+
+        let mut header = self.severity.to_string();
+
+        if let Some(code) = &self.code {
+            header = format!("{header}[{code}]");
+        }
+
+        let mut string = format!(
+            "{}{}\n",
+            paint(self.severity.color(), &header),
+            paint(BOLD, &format!(": {}", self.message))
+        );
+
+        let span = match self.span {
+            Some(span) => span,
+            None => {
+                string.push_str(&format!("{} {name}\n", paint(BLUE, "-->")));
+                for note in &self.notes {
+                    string.push_str(&format!("{} note: {note}\n", paint(BLUE, "=")));
+                }
+                return string;
+            }
+        };
+
+        let line = source
+            .lines()
+            .nth(span.line().saturating_sub(1))
+            .unwrap_or("");
+
+        let gutter = span.line().to_string().len();
+
+        let pad = " ".repeat(gutter);
+
+        string.push_str(&format!(
+            "{pad}{} {name}:{}:{}\n",
+            paint(BLUE, "-->"),
+            span.line(),
+            span.column()
+        ));
+
+        string.push_str(&format!("{pad} {}\n", paint(BLUE, "|")));
+
+        string.push_str(&format!(
+            "{} {}\n",
+            paint(BLUE, &format!("{} |", span.line())),
+            line
+        ));
+
+        // underline up to the end of the first line of the span.
+        let column = span.column().saturating_sub(1);
+
+        let offset = line
+            .char_indices()
+            .nth(column)
+            .map(|(offset, _)| offset)
+            .unwrap_or(line.len());
+
+        let end = (offset + span.len()).min(line.len());
+
+        let width = line
+            .get(offset..end)
+            .map(|text| text.chars().count())
+            .unwrap_or(0)
+            .max(1);
+
+        let indent = line
+            .chars()
+            .take(column)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        let mut marker = "^".repeat(width);
+
+        if let Some(label) = &self.label {
+            marker = format!("{marker} {label}");
+        }
+
+        string.push_str(&format!(
+            "{pad} {} {indent}{}\n",
+            paint(BLUE, "|"),
+            paint(self.severity.color(), &marker)
+        ));
+
+        for note in &self.notes {
+            string.push_str(&format!("{pad} {} note: {note}\n", paint(BLUE, "=")));
+        }
+
+        string
+    }
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        let mut diagnostic = Diagnostic::new(Severity::Error, error.message().to_owned())
+            .with_code(error.kind().code());
+
+        if let Some(span) = error.span() {
+            diagnostic = diagnostic.with_span(span);
+        }
+
+        if let Some(label) = error.label() {
+            diagnostic = diagnostic.with_label(label);
+        }
+
+        diagnostic
+    }
+}
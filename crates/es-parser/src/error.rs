@@ -1,29 +1,167 @@
-pub type Result<T> = std::result::Result<T,Error>;
+use crate::token::Token;
+use es_ast::Span;
+use std::fmt::Display;
+use std::fmt::Formatter;
 
+pub type Result<T> = std::result::Result<T, Error>;
+
+// boxed, so a Result carrying it stays small.
 #[derive(Debug, Clone)]
 pub struct Error {
+    inner: Box<ErrorInner>,
+}
+
+#[derive(Debug, Clone)]
+struct ErrorInner {
     kind: ErrorKind,
     message: String,
+    span: Option<Span>,
+    expected: Vec<Token>,
+    found: Option<Token>,
 }
 
 impl Error {
     pub fn new(kind: ErrorKind, message: String) -> Self {
         Self {
-            kind: kind,
-            message: message,
+            inner: Box::new(ErrorInner {
+                kind: kind,
+                message: message,
+                span: None,
+                expected: Vec::new(),
+                found: None,
+            }),
+        }
+    }
+
+    // found is None when the input ended before the expected token.
+    pub fn unexpected(expected: Vec<Token>, found: Option<Token>, span: Span) -> Self {
+        let (kind, message) = match &found {
+            None | Some(Token::EOF) => (
+                ErrorKind::UnexpectedEof,
+                "unexpected end of input".to_owned(),
+            ),
+            Some(token) => (
+                ErrorKind::UnexpectedToken,
+                format!("unexpected {}", token.name()),
+            ),
+        };
+
+        Self {
+            inner: Box::new(ErrorInner {
+                kind: kind,
+                message: message,
+                span: Some(span),
+                expected: expected,
+                found: found,
+            }),
         }
     }
 
+    pub fn with_kind(mut self, kind: ErrorKind, message: String) -> Self {
+        self.inner.kind = kind;
+        self.inner.message = message;
+        self
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.inner.span = Some(span);
+        self
+    }
+
     pub fn message(&self) -> &str {
-        &self.message
+        &self.inner.message
     }
 
     pub fn kind(&self) -> &ErrorKind {
-        &self.kind
+        &self.inner.kind
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.inner.span
+    }
+
+    pub fn expected(&self) -> &[Token] {
+        &self.inner.expected
+    }
+
+    pub fn found(&self) -> Option<&Token> {
+        self.inner.found.as_ref()
+    }
+
+    // true when the input ended before the statement did, e.g. after an
+    // unclosed `{`, a trailing `|` or inside a string. more input may fix it.
+    pub fn is_incomplete(&self) -> bool {
+        match self.inner.kind {
+            ErrorKind::UnexpectedEof | ErrorKind::UnterminatedString => true,
+            ErrorKind::UnmatchedBrace | ErrorKind::UnmatchedParen => {
+                matches!(self.inner.found, None | Some(Token::EOF))
+            }
+            _ => false,
+        }
+    }
+
+    // short description of what was expected, e.g. "expected `{` or string".
+    pub fn label(&self) -> Option<String> {
+        if self.inner.expected.is_empty() {
+            return None;
+        }
+
+        let mut names = self
+            .inner
+            .expected
+            .iter()
+            .map(|token| token.name())
+            .collect::<Vec<String>>();
+
+        names.dedup();
+
+        let label = match names.len() {
+            1 => names[0].to_owned(),
+            _ => {
+                let last = names.pop().unwrap_or_default();
+                format!("{} or {}", names.join(", "), last)
+            }
+        };
+
+        Some(format!("expected {label}"))
     }
 }
 
-#[derive(Debug, Clone)]
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self.label() {
+            Some(label) => write!(f, "{}, {}", self.inner.message, label),
+            None => write!(f, "{}", self.inner.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum ErrorKind {
     Unknown,
+    UnexpectedToken,
+    UnexpectedEof,
+    UnterminatedString,
+    UnmatchedBrace,
+    UnmatchedParen,
+    MissingRedirectTarget,
+    InvalidFd,
+}
+
+impl ErrorKind {
+    // stable identifier used in rendered diagnostics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::Unknown => "E0000",
+            ErrorKind::UnexpectedToken => "E0001",
+            ErrorKind::UnexpectedEof => "E0002",
+            ErrorKind::UnterminatedString => "E0003",
+            ErrorKind::UnmatchedBrace => "E0004",
+            ErrorKind::UnmatchedParen => "E0005",
+            ErrorKind::MissingRedirectTarget => "E0006",
+            ErrorKind::InvalidFd => "E0007",
+        }
+    }
 }
@@ -1,25 +1,107 @@
+use std::collections::VecDeque;
 use std::mem;
 
+use crate::error::Error;
+use crate::error::ErrorKind;
 use crate::token::Token;
+use es_ast::Span;
 
 #[derive(Debug)]
 pub struct Lexer {
     input: Vec<char>,
+    // byte offset of each char in input, plus the total length at the end.
+    offsets: Vec<usize>,
+    // char index of the first char of each line.
+    lines: Vec<usize>,
     position: usize,
+    start: usize,
     is_eof: bool,
+    // emit Token::Comment instead of skipping comments.
+    comments: bool,
     // prev: Option<Token>,
-    peek: Option<Token>,
+    peek: Option<(Token, Span)>,
+    // tokens read ahead by nth or put in place of others by splice, returned
+    // before anything else is read.
+    pending: VecDeque<(Token, Span)>,
+    span: Span,
+    errors: Vec<Error>,
 }
 
 impl Lexer {
     pub fn new(string: &str) -> Self {
+        let input = string.chars().collect::<Vec<char>>();
+
+        let mut offsets = Vec::with_capacity(input.len() + 1);
+
+        let mut lines = vec![0];
+
+        let mut offset = 0;
+
+        for (i, ch) in input.iter().enumerate() {
+            offsets.push(offset);
+            offset += ch.len_utf8();
+
+            if ch == &'\n' {
+                lines.push(i + 1);
+            }
+        }
+
+        offsets.push(offset);
+
         Self {
-            input: string.chars().collect(),
+            input: input,
+            offsets: offsets,
+            lines: lines,
             position: 0,
+            start: 0,
             is_eof: false,
+            comments: false,
             // prev: None,
             peek: None,
+            pending: VecDeque::new(),
+            span: Span::default(),
+            errors: Vec::new(),
+        }
+    }
+
+    // errors found while reading tokens, e.g. an unterminated string.
+    pub fn take_errors(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.errors)
+    }
+
+    // a lexer that keeps comments as Token::Comment, for tools that rewrite source.
+    pub fn with_comments(string: &str) -> Self {
+        let mut lexer = Self::new(string);
+
+        lexer.comments = true;
+
+        lexer
+    }
+
+    // span of the token most recently returned by next.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn peek_span(&mut self) -> Option<Span> {
+        if self.peek.is_none() {
+            self.peek = self.read_with_span();
+        }
+
+        self.peek.as_ref().map(|(_, span)| *span)
+    }
+
+    pub fn next_with_span(&mut self) -> Option<(Token, Span)> {
+        let token = match self.peek.take() {
+            Some(token) => Some(token),
+            None => self.read_with_span(),
+        };
+
+        if let Some((_, span)) = &token {
+            self.span = *span;
         }
+
+        token
     }
 
     pub fn consume(&mut self) {
@@ -33,7 +115,7 @@ impl Lexer {
         }
 
         match &self.peek {
-            Some(peek) => mem::discriminant(peek) == mem::discriminant(token),
+            Some((peek, _)) => mem::discriminant(peek) == mem::discriminant(token),
             None => false,
         }
     }
@@ -44,23 +126,136 @@ impl Lexer {
 
     pub fn peek(&mut self) -> Option<&Token> {
         if self.peek.is_none() {
-            self.peek = self.read();
+            self.peek = self.read_with_span();
+        }
+
+        self.peek.as_ref().map(|(token, _)| token)
+    }
+
+    // the token n places after the next one, without consuming anything. nth(0)
+    // is the same as peek.
+    pub fn nth(&mut self, n: usize) -> Option<&(Token, Span)> {
+        if let Some(peek) = self.peek.take() {
+            self.pending.push_front(peek);
+        }
+
+        while self.pending.len() <= n {
+            match self.read_input() {
+                Some(token) => self.pending.push_back(token),
+                None => break,
+            }
         }
 
-        self.peek.as_ref()
+        self.pending.get(n)
+    }
+
+    // replaces the token n places after the next one with tokens.
+    pub fn splice(&mut self, n: usize, tokens: Vec<(Token, Span)>) {
+        if self.nth(n).is_none() {
+            return;
+        }
+
+        self.pending.remove(n);
+
+        for (i, token) in tokens.into_iter().enumerate() {
+            self.pending.insert(n + i, token);
+        }
+    }
+
+    // whether the token at span was written in double quotes.
+    pub fn is_quoted(&self, span: &Span) -> bool {
+        match self.offsets.binary_search(&span.start()) {
+            Ok(i) => self.input.get(i) == Some(&'"'),
+            Err(_) => false,
+        }
+    }
+
+    // the input under span as written. None for a span that is not in it.
+    pub fn text(&self, span: &Span) -> Option<String> {
+        let start = self.offsets.binary_search(&span.start()).ok()?;
+
+        let end = self.offsets.binary_search(&span.end()).ok()?;
+
+        self.input
+            .get(start..end)
+            .map(|chars| chars.iter().collect())
+    }
+
+    fn read_with_span(&mut self) -> Option<(Token, Span)> {
+        match self.pending.pop_front() {
+            Some(token) => Some(token),
+            None => self.read_input(),
+        }
+    }
+
+    fn read_input(&mut self) -> Option<(Token, Span)> {
+        let token = self.read()?;
+
+        Some((token, self.make_span(self.start, self.position)))
+    }
+
+    fn make_span(&self, start: usize, end: usize) -> Span {
+        let line = match self.lines.binary_search(&start) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+
+        Span::new(
+            self.offsets[start],
+            self.offsets[end],
+            line + 1,
+            start - self.lines[line] + 1,
+        )
     }
 
     fn read(&mut self) -> Option<Token> {
         while let Some(ch) = self.input.get(self.position) {
-            if ch.is_whitespace() {
+            if ch.is_whitespace() && ch != &'\n' {
                 self.position += 1;
                 continue;
             }
 
+            self.start = self.position;
+
+            // backslash newline continues the line.
+            if ch == &'\\' {
+                match self.peek_ch() {
+                    Some('\n') => {
+                        self.position += 2;
+                        continue;
+                    }
+                    None => {
+                        self.position += 1;
+                        self.errors.push(
+                            Error::new(
+                                ErrorKind::UnexpectedEof,
+                                "unexpected end of input after `\\`".to_owned(),
+                            )
+                            .with_span(self.make_span(self.start, self.position)),
+                        );
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
             match ch {
-                '#' => self.skip_commentout(),
+                '#' => {
+                    let origin = self.position;
 
-                '\n' => return Some(Token::EOL),
+                    self.skip_commentout();
+
+                    if self.comments {
+                        return Some(Token::Comment(
+                            self.input[origin + 1..self.position].iter().collect(),
+                        ));
+                    }
+                }
+
+                '\n' => {
+                    self.position += 1;
+                    return Some(Token::EOL);
+                }
 
                 // pipe ∨ or
                 '|' => {
@@ -123,16 +318,12 @@ impl Lexer {
 
                 // dollar ∨ ident
                 '$' => {
-                    if let Some(ch) = self.peek_ch() {
-                        if ch.is_whitespace() == false {
-                            self.position += 1;
-                            if let Some(string) = self.read_string(false) {
-                                return Some(Token::Ident(string));
-                            }
-                        }
+                    self.position += 1;
+
+                    if let Some(string) = self.read_string(false) {
+                        return Some(Token::Ident(string));
                     }
 
-                    self.position += 1;
                     return Some(Token::Dollar);
                 }
 
@@ -143,15 +334,21 @@ impl Lexer {
                         return Some(Token::AND);
                     }
 
-                    if let Some(ch) = self.peek_ch() {
-                        if ch.is_whitespace() == false {
-                            self.position += 1;
-                            if let Some(n) = self.read_u32() {
-                                {
-                                    return Some(Token::FD(n));
-                                }
-                            }
+                    if matches!(self.peek_ch(), Some(ch) if ch.is_alphanumeric()) {
+                        self.position += 1;
+
+                        if let Some(n) = self.read_u32() {
+                            return Some(Token::FD(n));
                         }
+
+                        let string = self.read_string(false).unwrap_or_default();
+
+                        self.errors.push(
+                            Error::new(ErrorKind::InvalidFd, format!("invalid fd `&{string}`"))
+                                .with_span(self.make_span(self.start, self.position)),
+                        );
+
+                        return Some(Token::String(format!("&{string}")));
                     }
 
                     self.position += 1;
@@ -185,10 +382,23 @@ impl Lexer {
                 '"' => {
                     self.position += 1;
 
-                    match self.read_string(true) {
-                        Some(string) => return Some(Token::String(string)),
-                        None => break,
+                    let string = self.read_string(true).unwrap_or_default();
+
+                    // read_string consumes the closing quote when there is one.
+                    let is_closed = self.position - 1 > self.start
+                        && matches!(self.input.get(self.position - 1), Some('"'));
+
+                    if is_closed == false {
+                        self.errors.push(
+                            Error::new(
+                                ErrorKind::UnterminatedString,
+                                "unterminated string".to_owned(),
+                            )
+                            .with_span(self.make_span(self.start, self.position)),
+                        );
                     }
+
+                    return Some(Token::String(string));
                 }
 
                 _ => {
@@ -203,8 +413,8 @@ impl Lexer {
                             "elif" => return Some(Token::Elif),
                             "else" => return Some(Token::Else),
                             "def" => return Some(Token::Def),
-                            "true"=>return Some(Token::True),
-                            "false"=>return Some(Token::False),
+                            "true" => return Some(Token::True),
+                            "false" => return Some(Token::False),
                             "return" => return Some(Token::Return),
                             _ => return Some(Token::String(string)),
                         }
@@ -213,6 +423,8 @@ impl Lexer {
             }
         }
 
+        self.start = self.position;
+
         match self.is_eof {
             true => None,
             false => {
@@ -276,6 +488,11 @@ impl Lexer {
                 {
                     break;
                 }
+
+                // leave a trailing backslash to read as a line continuation.
+                if ch == &'\\' && matches!(self.peek_ch(), None | Some('\n')) {
+                    break;
+                }
             }
 
             self.position += 1;
@@ -298,11 +515,12 @@ impl Lexer {
             return;
         }
 
+        // the newline itself is left for the EOL token.
         while let Some(ch) = self.input.get(self.position) {
-            self.position += 1;
             if ch == &'\n' {
                 break;
             }
+            self.position += 1;
         }
     }
 }
@@ -311,15 +529,8 @@ impl Iterator for Lexer {
     type Item = Token;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(token) = self.peek.take() {
-            // self.prev = Some(token.clone());
-            return Some(token);
-        }
-
-        // let token = self.read();
-
         // self.prev = token.clone();
 
-        self.read()
+        self.next_with_span().map(|(token, _)| token)
     }
 }
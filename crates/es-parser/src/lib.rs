@@ -1,142 +1,899 @@
+pub mod diagnostic;
 pub mod error;
 pub mod lexer;
 pub mod token;
 use error::*;
 use es_ast::*;
 use lexer::*;
+use std::collections::BTreeMap;
 use token::*;
 
+// an interactive caller should read another line instead of reporting errors.
+pub fn is_incomplete(source: &str) -> bool {
+    let (_, errors) = Parser::new(Lexer::new(source)).parse();
+
+    errors.iter().any(|err| err.is_incomplete())
+}
+
+// where the word text ends with starts, after the last blank or operator. a
+// completion replaces the text from there.
+pub fn word_start(text: &str) -> usize {
+    text.char_indices()
+        .rev()
+        .find(|(_, ch)| ch.is_whitespace() || "|;&(){}<>=,\"".contains(*ch))
+        .map(|(index, ch)| index + ch.len_utf8())
+        .unwrap_or(0)
+}
+
+// where the command text ends in starts, after the last separator.
+pub fn command_start(text: &str) -> usize {
+    text.rfind(|ch: char| "\n|;&(){}".contains(ch))
+        .map(|index| index + 1)
+        .unwrap_or(0)
+}
+
 pub struct Parser {
     lexer: Lexer,
+    errors: Vec<Error>,
+    // set when the end of line after a statement was already consumed while
+    // looking for a continuation, such as `elif` after an if block, or when the
+    // statement ended with `&`.
+    is_statement_end: bool,
+    // alias names and their text, expanded where a command name is read.
+    aliases: BTreeMap<String, String>,
 }
 
 impl Parser {
     pub fn new(lexer: Lexer) -> Self {
-        Self { lexer: lexer }
+        Self {
+            lexer: lexer,
+            errors: Vec::new(),
+            is_statement_end: false,
+            aliases: BTreeMap::new(),
+        }
+    }
+
+    pub fn set_aliases(&mut self, aliases: BTreeMap<String, String>) {
+        self.aliases = aliases;
     }
 
-    pub fn parse(&mut self) {}
+    // parses the whole input. a statement that fails to parse is skipped up to the
+    // next `;`, newline or `}`, so every syntax error in the input is reported and
+    // the returned block holds whatever could be parsed.
+    pub fn parse(&mut self) -> (Block, Vec<Error>) {
+        let start = self.lexer.peek_span().unwrap_or_default();
+
+        let mut statements = Vec::new();
+
+        loop {
+            self.skip_separator();
+
+            let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+            match self.lexer.peek() {
+                None | Some(Token::EOF) => break,
+
+                Some(Token::RBrace) => {
+                    self.lexer.consume();
+
+                    self.errors.push(
+                        Error::unexpected(Vec::new(), Some(Token::RBrace), span)
+                            .with_kind(ErrorKind::UnmatchedBrace, "unmatched `}`".to_owned()),
+                    );
+
+                    continue;
+                }
+
+                _ => {}
+            }
+
+            self.parse_statement_or_recover(&mut statements);
+        }
+
+        let mut block = Block::from(statements);
+
+        block.set_span(start.to(&self.lexer.span()));
 
-    pub fn parse_assign(&mut self) {}
+        (block, self.take_errors())
+    }
+
+    pub fn parse_statement(&mut self) -> Result<Statement> {
+        let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+        match self.lexer.peek() {
+            Some(Token::If) => Ok(Statement::If(self.parse_if()?)),
+
+            Some(Token::Loop) => Ok(Statement::Loop(self.parse_loop()?)),
+
+            Some(Token::Def) => Ok(Statement::Def(self.parse_def()?)),
+
+            Some(Token::Return) => Ok(Statement::Return(self.parse_return()?)),
+
+            Some(Token::LBrace) => Ok(Statement::Block(self.parse_block()?)),
+
+            Some(Token::Ident(_)) => {
+                let name = match self.lexer.next() {
+                    Some(Token::Ident(name)) => name,
+                    _ => Err(Error::unexpected(expected_word(), None, span))?,
+                };
+
+                if self.lexer.next_is(&Token::Assign) {
+                    return Ok(Statement::Assign(
+                        self.parse_assign_value(Expression::Variable(name), span)?,
+                    ));
+                }
+
+                Ok(Statement::Expression(
+                    self.parse_logical(Some((Expression::Variable(name), span)))?,
+                ))
+            }
+
+            _ => Ok(Statement::Expression(self.parse_logical(None)?)),
+        }
+    }
+
+    pub fn parse_assign(&mut self) -> Result<Assign> {
+        let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+        match self.lexer.peek() {
+            Some(Token::Ident(_)) => {}
+            token => Err(Error::unexpected(
+                vec![Token::Ident(String::new())],
+                token.cloned(),
+                span,
+            ))?,
+        }
+
+        match self.lexer.next() {
+            Some(Token::Ident(name)) => self.parse_assign_value(Expression::Variable(name), span),
+            token => Err(Error::unexpected(
+                vec![Token::Ident(String::new())],
+                token,
+                span,
+            )),
+        }
+    }
 
     pub fn parse_command(&mut self) -> Result<Command> {
+        let start = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
         let prefix = match self.parse_command_prefix() {
             Err(err) => Err(err)?,
             Ok(prefix) => prefix,
         };
 
-        let mut command = Command::new(prefix);
+        let command = self.parse_command_with_prefix(prefix, start)?;
 
-        match self.parse_command_suffix() {
-            Err(err) => Err(err)?,
-            Ok(ok) => {
-                if let Some(suffix) = ok {
-                    command.insert_suffix(suffix);
+        self.lexer_error()?;
+
+        Ok(command)
+    }
+
+    fn parse_statement_or_recover(&mut self, statements: &mut Vec<Statement>) {
+        match self.parse_statement().and_then(|statement| {
+            self.expect_statement_end()?;
+            Ok(statement)
+        }) {
+            Ok(statement) => statements.push(statement),
+            Err(err) => {
+                self.is_statement_end = false;
+                self.errors.push(err);
+                self.synchronize();
+            }
+        }
+    }
+
+    // skips tokens up to the end of the broken statement. braces opened on the way
+    // are skipped as a whole, a `}` closing the enclosing block is left in place.
+    fn synchronize(&mut self) {
+        let mut depth = 0;
+
+        while let Some(token) = self.lexer.peek().cloned() {
+            match token {
+                Token::EOF => break,
+
+                Token::Semicolon | Token::EOL if depth == 0 => {
+                    self.lexer.consume();
+                    break;
                 }
+
+                Token::RBrace if depth == 0 => break,
+
+                Token::LBrace => depth += 1,
+
+                Token::RBrace => depth -= 1,
+
+                _ => {}
             }
+
+            self.lexer.consume();
         }
+    }
 
-        Ok(command)
+    fn expect_statement_end(&mut self) -> Result<()> {
+        if self.is_statement_end {
+            self.is_statement_end = false;
+            return Ok(());
+        }
+
+        let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+        match self.lexer.peek() {
+            None
+            | Some(Token::Semicolon)
+            | Some(Token::EOL)
+            | Some(Token::EOF)
+            | Some(Token::RBrace) => Ok(()),
+
+            Some(token) => Err(Error::unexpected(
+                vec![Token::Semicolon, Token::EOL],
+                Some(token.clone()),
+                span,
+            )),
+        }
     }
 
-    fn parse_command_suffix(&mut self) -> Result<Option<CommandSuffix>> {
-        // return if token is Pipe || Semicolon || EOL || EOF
-        if matches!(
-            self.lexer.peek(),
-            None | Some(Token::Pipe) | Some(Token::Semicolon) | Some(Token::EOL) | Some(Token::EOF)
-        ) {
-            return Ok(None);
+    fn skip_separator(&mut self) {
+        while self.lexer.next_is(&Token::Semicolon) || self.lexer.next_is(&Token::EOL) {
+            self.lexer.consume();
         }
+    }
 
-        let mut suffix = CommandSuffix::new();
+    fn skip_eol(&mut self) {
+        while self.lexer.next_is(&Token::EOL) {
+            self.lexer.consume();
+        }
+    }
 
-        while let Some(token) = self.lexer.next() {
-            if let Ok(fd) = parse_fd(&token) {
-                match self.lexer.peek() {
-                    Some(Token::Gt) => {
-                        self.lexer.consume();
+    fn take_errors(&mut self) -> Vec<Error> {
+        let mut errors = std::mem::take(&mut self.errors);
 
-                        match self.lexer.next() {
-                            None => Err(Error::new(ErrorKind::Unknown, "".to_owned()))?,
-                            Some(token) => {
-                                match parse_string(&token)
-                                    .or(parse_number(&token).or(parse_fd(&token)))
-                                {
-                                    Err(err) => Err(err)?,
-                                    Ok(expr) => {
-                                        suffix.insert(Expression::Redirect(Redirect::new(
-                                            RedirectKind::Write,
-                                            fd,
-                                            expr,
-                                        )));
-                                    }
-                                }
-                            }
-                        }
+        errors.append(&mut self.lexer.take_errors());
+
+        errors.sort_by_key(|err| err.span().map(|span| span.start()).unwrap_or(usize::MAX));
+
+        errors
+    }
+
+    // { statement; statement }
+    fn parse_block(&mut self) -> Result<Block> {
+        let open = self.expect(Token::LBrace)?;
+
+        let mut statements = Vec::new();
+
+        loop {
+            self.skip_separator();
+
+            match self.lexer.peek() {
+                Some(Token::RBrace) => {
+                    self.lexer.consume();
+                    break;
+                }
+
+                None | Some(Token::EOF) => {
+                    self.errors.push(
+                        Error::unexpected(vec![Token::RBrace], Some(Token::EOF), open)
+                            .with_kind(ErrorKind::UnmatchedBrace, "unclosed `{`".to_owned()),
+                    );
+                    break;
+                }
+
+                _ => {}
+            }
+
+            self.parse_statement_or_recover(&mut statements);
+        }
+
+        let mut block = Block::from(statements);
+
+        block.set_span(open.to(&self.lexer.span()));
+
+        Ok(block)
+    }
+
+    // if comparison { } elif comparison { } else { }
+    fn parse_if(&mut self) -> Result<If> {
+        let start = self.expect(Token::If)?;
+
+        let mut node = If::new(self.parse_comparison()?);
+
+        node.insert_block(self.parse_block()?);
+
+        loop {
+            let end = self.lexer.span();
+
+            let is_eol = self.lexer.next_is(&Token::EOL);
+
+            self.skip_eol();
+
+            match self.lexer.peek() {
+                Some(Token::Elif) => {
+                    let span = self.expect(Token::Elif)?;
+
+                    let mut child = If::new(self.parse_comparison()?);
+
+                    child.insert_block(self.parse_block()?);
+
+                    child.set_span(span.to(&self.lexer.span()));
+
+                    node.insert_child(child);
+                }
+
+                Some(Token::Else) => {
+                    self.lexer.consume();
+
+                    node.insert_else(self.parse_block()?);
+
+                    break;
+                }
+
+                _ => {
+                    self.is_statement_end = is_eol;
+
+                    node.set_span(start.to(&end));
+
+                    return Ok(node);
+                }
+            }
+        }
+
+        node.set_span(start.to(&self.lexer.span()));
+
+        Ok(node)
+    }
+
+    // loop comparison { }
+    fn parse_loop(&mut self) -> Result<Loop> {
+        let start = self.expect(Token::Loop)?;
+
+        let mut node = Loop::new(self.parse_comparison()?);
+
+        node.insert(self.parse_block()?);
+
+        node.set_span(start.to(&self.lexer.span()));
+
+        Ok(node)
+    }
+
+    // def name($a, $b) { }
+    fn parse_def(&mut self) -> Result<Def> {
+        let start = self.expect(Token::Def)?;
+
+        let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+        let name = match self.lexer.peek() {
+            Some(Token::String(name)) => name.to_owned(),
+            token => Err(Error::unexpected(
+                vec![Token::String(String::new())],
+                token.cloned(),
+                span,
+            ))?,
+        };
+
+        self.lexer.consume();
+
+        let mut params = Vec::new();
+
+        if self.lexer.next_is(&Token::LParen) {
+            let open = self.expect(Token::LParen)?;
+
+            loop {
+                let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+                match self.lexer.peek().cloned() {
+                    Some(Token::RParen) => {
+                        self.lexer.consume();
+                        break;
                     }
-                    Some(Token::Lt) => {
+
+                    Some(Token::Ident(param)) => {
                         self.lexer.consume();
 
-                        match self.lexer.next() {
-                            None => Err(Error::new(ErrorKind::Unknown, "".to_owned()))?,
-                            Some(token) => {
-                                match parse_string(&token)
-                                    .or(parse_number(&token).or(parse_fd(&token)))
-                                {
-                                    Err(err) => Err(err)?,
-                                    Ok(expr) => {
-                                        suffix.insert(Expression::Redirect(Redirect::new(
-                                            RedirectKind::Read,
-                                            fd,
-                                            expr,
-                                        )));
-                                    }
-                                }
-                            }
+                        params.push(param);
+
+                        if self.lexer.next_is(&Token::Comma) {
+                            self.lexer.consume();
                         }
                     }
-                    _ => {
-                        suffix.insert(fd);
-                    }
+
+                    None | Some(Token::EOF) | Some(Token::LBrace) => Err(Error::unexpected(
+                        vec![Token::RParen],
+                        self.lexer.peek().cloned(),
+                        open,
+                    )
+                    .with_kind(ErrorKind::UnmatchedParen, "unclosed `(`".to_owned()))?,
+
+                    token => Err(Error::unexpected(
+                        vec![Token::Ident(String::new()), Token::RParen],
+                        token,
+                        span,
+                    ))?,
                 }
-            } else {
-                if let Ok(expr) = parse_variable(&token) {
-                    suffix.insert(expr);
-                } else {
-                    match token {
-                        Token::Ampersand => {
-                            suffix.insert(Expression::Background(true));
-                            break;
+            }
+        }
+
+        let mut node = Def::new(name, params);
+
+        node.insert_block(self.parse_block()?);
+
+        node.set_span(start.to(&self.lexer.span()));
+
+        Ok(node)
+    }
+
+    // return, return value
+    fn parse_return(&mut self) -> Result<Return> {
+        let start = self.expect(Token::Return)?;
+
+        let expr = match self.lexer.peek() {
+            None
+            | Some(Token::Semicolon)
+            | Some(Token::EOL)
+            | Some(Token::EOF)
+            | Some(Token::RBrace) => None,
+            _ => Some(self.parse_value()?),
+        };
+
+        let mut node = Return::new(expr);
+
+        node.set_span(start.to(&self.lexer.span()));
+
+        Ok(node)
+    }
+
+    // $name = value, the name has already been consumed.
+    fn parse_assign_value(&mut self, identify: Expression, start: Span) -> Result<Assign> {
+        self.expect(Token::Assign)?;
+
+        let mut node = Assign::new(identify, self.parse_value()?);
+
+        node.set_span(start.to(&self.lexer.span()));
+
+        Ok(node)
+    }
+
+    // value == value, value != value, value > value, value < value
+    fn parse_comparison(&mut self) -> Result<Comparison> {
+        let start = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+        let left = self.parse_value()?;
+
+        let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+        let kind = match self.lexer.peek() {
+            Some(Token::Equal) => ComparisonKind::Equal,
+            Some(Token::NotEqual) => ComparisonKind::NotEqual,
+            Some(Token::Gt) => ComparisonKind::Gt,
+            Some(Token::Lt) => ComparisonKind::Lt,
+            token => Err(Error::unexpected(
+                vec![Token::Equal, Token::NotEqual, Token::Gt, Token::Lt],
+                token.cloned(),
+                span,
+            ))?,
+        };
+
+        self.lexer.consume();
+
+        let right = self.parse_value()?;
+
+        let mut node = Comparison::new(kind, left, right);
+
+        node.set_span(start.to(&self.lexer.span()));
+
+        Ok(node)
+    }
+
+    fn parse_value(&mut self) -> Result<Expression> {
+        let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+        let expr = match self.lexer.peek() {
+            Some(Token::True) => Expression::Boolean(true),
+            Some(Token::False) => Expression::Boolean(false),
+            Some(token) => match parse_string(token)
+                .or(parse_variable(token))
+                .or(parse_number(token))
+                .or(parse_fd(token))
+            {
+                Ok(expr) => expr,
+                Err(_) => Err(Error::unexpected(
+                    expected_value(),
+                    Some(token.clone()),
+                    span,
+                ))?,
+            },
+            None => Err(Error::unexpected(expected_value(), None, span))?,
+        };
+
+        self.lexer.consume();
+
+        Ok(expr)
+    }
+
+    // pipe && pipe || pipe
+    fn parse_logical(&mut self, prefix: Option<(Expression, Span)>) -> Result<Expression> {
+        let start = match &prefix {
+            Some((_, span)) => *span,
+            None => self.lexer.peek_span().unwrap_or(self.lexer.span()),
+        };
+
+        let mut left = self.parse_timed(prefix)?;
+
+        loop {
+            let kind = match self.lexer.peek() {
+                Some(Token::AND) => LogicalKind::And,
+                Some(Token::OR) => LogicalKind::Or,
+                _ => break,
+            };
+
+            self.lexer.consume();
+
+            self.skip_eol();
+
+            let right = self.parse_timed(None)?;
+
+            let mut node = Logical::new(kind, left, right);
+
+            node.set_span(start.to(&self.lexer.span()));
+
+            left = Expression::Logical(node);
+        }
+
+        Ok(left)
+    }
+
+    // time pipe, or a pipe on its own.
+    fn parse_timed(&mut self, prefix: Option<(Expression, Span)>) -> Result<Expression> {
+        if prefix.is_some() || self.is_time() == false {
+            return self.parse_pipe(prefix);
+        }
+
+        self.lexer.consume();
+
+        let start = self.lexer.span();
+
+        let mut node = Time::new(self.parse_pipe(None)?);
+
+        node.set_span(start.to(&self.lexer.span()));
+
+        Ok(Expression::Time(node))
+    }
+
+    // time is a keyword only where a pipeline starts, unquoted and not as the
+    // name of time=value. anywhere else it is a word.
+    fn is_time(&mut self) -> bool {
+        let span = match self.lexer.nth(0) {
+            Some((Token::String(word), span)) if word == "time" => *span,
+            _ => return false,
+        };
+
+        if self.lexer.is_quoted(&span) {
+            return false;
+        }
+
+        match self.lexer.nth(1) {
+            Some((Token::Assign, next)) => next.start() != span.end(),
+            _ => true,
+        }
+    }
+
+    // command | command | command
+    fn parse_pipe(&mut self, prefix: Option<(Expression, Span)>) -> Result<Expression> {
+        let command = match prefix {
+            Some((prefix, span)) => self.parse_command_with_prefix(prefix, span)?,
+            None => {
+                let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+                let prefix = self.parse_command_prefix()?;
+                self.parse_command_with_prefix(prefix, span)?
+            }
+        };
+
+        if self.lexer.next_is(&Token::Pipe) == false {
+            return Ok(Expression::Command(command));
+        }
+
+        let start = command.span();
+
+        self.lexer.consume();
+
+        self.skip_eol();
+
+        let mut pipe = Pipe::new();
+
+        pipe.insert_left(Expression::Command(command));
+
+        pipe.insert_right(self.parse_pipe(None)?);
+
+        pipe.set_span(start.to(&self.lexer.span()));
+
+        Ok(Expression::Pipe(pipe))
+    }
+
+    fn parse_command_with_prefix(&mut self, prefix: Expression, start: Span) -> Result<Command> {
+        let mut command = Command::new(prefix);
+
+        match self.parse_command_suffix() {
+            Err(err) => Err(err)?,
+            Ok(suffix) => command.insert_suffix(suffix),
+        }
+
+        command.set_span(start.to(&self.lexer.span()));
+
+        Ok(command)
+    }
+
+    fn parse_command_suffix(&mut self) -> Result<CommandSuffix> {
+        let mut suffix = CommandSuffix::new();
+
+        while self.is_command_end() == false {
+            let (token, span) = match self.lexer.next_with_span() {
+                Some(token) => token,
+                None => break,
+            };
+
+            // a number written against > or <, as in 3>file, is the fd they redirect.
+            let token = match token {
+                Token::Number(fd)
+                    if fd >= 0
+                        && (self.is_adjacent(&span, &Token::Gt)
+                            || self.is_adjacent(&span, &Token::Lt)) =>
+                {
+                    Token::FD(fd as u32)
+                }
+                token => token,
+            };
+
+            match token {
+                Token::FD(_) | Token::Gt | Token::Lt => {
+                    // a bare > or < redirects stdout or stdin.
+                    let (fd, kind) = match (&token, self.lexer.peek()) {
+                        (Token::Gt, _) => (Expression::FD(1), Some(RedirectKind::Write)),
+                        (Token::Lt, _) => (Expression::FD(0), Some(RedirectKind::Read)),
+                        (Token::FD(fd), Some(Token::Gt)) => {
+                            (Expression::FD(*fd), Some(RedirectKind::Write))
+                        }
+                        (Token::FD(fd), Some(Token::Lt)) => {
+                            (Expression::FD(*fd), Some(RedirectKind::Read))
                         }
+                        (Token::FD(fd), _) => (Expression::FD(*fd), None),
+                        _ => (Expression::String(token.to_string()), None),
+                    };
+
+                    match kind {
+                        Some(kind) => {
+                            if matches!(token, Token::FD(_)) {
+                                self.lexer.consume();
+                            }
+
+                            let redirect = self.parse_redirect(kind, fd, span)?;
 
-                        _ => {
-                            suffix.insert(Expression::String(token.to_string()));
+                            suffix.insert_with_span(redirect, span.to(&self.lexer.span()));
                         }
+                        None => suffix.insert_with_span(fd, span),
                     }
                 }
+
+                Token::Ident(_) => {
+                    suffix.insert_with_span(parse_variable(&token)?, span);
+                }
+
+                // `&` ends the statement like `;`, `a & b` runs b after starting a.
+                Token::Ampersand => {
+                    suffix.insert_with_span(Expression::Background(true), span);
+                    self.is_statement_end = true;
+                    break;
+                }
+
+                Token::String(name) if self.is_adjacent(&span, &Token::Assign) => {
+                    let (word, span) = self.parse_word_assign(name, span);
+
+                    let (word, span) = self.parse_word_list(word, span);
+
+                    suffix.insert_with_span(Expression::String(word), span);
+                }
+
+                _ => {
+                    let (word, span) = self.parse_word_list(token.to_string(), span);
+
+                    suffix.insert_with_span(Expression::String(word), span);
+                }
             }
+        }
 
-            if self.lexer.next_is(&Token::Pipe)
-                || self.lexer.next_is(&Token::Semicolon)
-                || self.lexer.next_is(&Token::EOL)
-                || self.lexer.next_is(&Token::EOF)
-            {
+        Ok(suffix)
+    }
+
+    // whether the next token is token and follows span without a space.
+    fn is_adjacent(&mut self, span: &Span, token: &Token) -> bool {
+        let is_next = self
+            .lexer
+            .peek_span()
+            .is_some_and(|next| next.start() == span.end());
+
+        is_next && self.lexer.next_is(token)
+    }
+
+    // name=value written as one argument, like for export or env. the `=` has
+    // not been consumed yet, the value may be left out.
+    fn parse_word_assign(&mut self, name: String, span: Span) -> (String, Span) {
+        self.lexer.consume();
+
+        let mut word = format!("{name}=");
+
+        let assign = self.lexer.span();
+
+        let is_value = self
+            .lexer
+            .peek_span()
+            .is_some_and(|next| next.start() == assign.end())
+            && matches!(
+                self.lexer.peek(),
+                Some(
+                    Token::String(_)
+                        | Token::Ident(_)
+                        | Token::Number(_)
+                        | Token::True
+                        | Token::False
+                )
+            );
+
+        if is_value {
+            if let Some(token) = self.lexer.next() {
+                word.push_str(&self.word_text(token, &self.lexer.span()));
+            }
+        }
+
+        (word, span.to(&self.lexer.span()))
+    }
+
+    // a token of a word as it reads in a string: a variable as ${name}, a number
+    // as written, so 02134 keeps its zeros.
+    fn word_text(&self, token: Token, span: &Span) -> String {
+        match token {
+            Token::Ident(name) => format!("${{{name}}}"),
+            // the tokens of an alias are not in the input.
+            Token::Number(number) => match self.lexer.text(span) {
+                Some(text) if text.parse::<isize>() == Ok(number) => text,
+                _ => number.to_string(),
+            },
+            token => token.to_string(),
+        }
+    }
+
+    // a,b,c written as one argument, like the modes of umask. the word before
+    // the first comma has been consumed.
+    fn parse_word_list(&mut self, mut word: String, mut span: Span) -> (String, Span) {
+        while self.is_adjacent(&span, &Token::Comma) {
+            self.lexer.consume();
+
+            word.push(',');
+
+            span = span.to(&self.lexer.span());
+
+            let comma = self.lexer.span();
+
+            let is_next = self
+                .lexer
+                .peek_span()
+                .is_some_and(|next| next.start() == comma.end());
+
+            if is_next == false {
                 break;
             }
+
+            let token = match self.lexer.peek() {
+                Some(
+                    token @ (Token::Ident(_)
+                    | Token::String(_)
+                    | Token::Number(_)
+                    | Token::True
+                    | Token::False),
+                ) => token.clone(),
+                _ => break,
+            };
+
+            self.lexer.consume();
+
+            let next_span = self.lexer.span();
+
+            let next = self.word_text(token, &next_span);
+
+            // g=rx in u=rwx,g=rx
+            let (next, next_span) = match self.is_adjacent(&next_span, &Token::Assign) {
+                true => self.parse_word_assign(next, next_span),
+                false => (next, next_span),
+            };
+
+            word.push_str(&next);
+
+            span = span.to(&next_span);
         }
 
-        Ok(Some(suffix))
+        (word, span)
+    }
+
+    // Pipe || Semicolon || EOL || EOF || && || || || }
+    fn is_command_end(&mut self) -> bool {
+        matches!(
+            self.lexer.peek(),
+            None | Some(Token::Pipe)
+                | Some(Token::Semicolon)
+                | Some(Token::EOL)
+                | Some(Token::EOF)
+                | Some(Token::AND)
+                | Some(Token::OR)
+                | Some(Token::RBrace)
+        )
+    }
+
+    // fd > target, fd < target. the operator has already been consumed.
+    fn parse_redirect(
+        &mut self,
+        kind: RedirectKind,
+        fd: Expression,
+        span: Span,
+    ) -> Result<Expression> {
+        let target_span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+        let target = match self.lexer.peek() {
+            None => None,
+            Some(token) => parse_string(token)
+                .or(parse_variable(token))
+                .or(parse_number(token).or(parse_fd(token)))
+                .ok(),
+        };
+
+        match target {
+            None => {
+                let found = self
+                    .lexer
+                    .peek()
+                    .map(|token| token.name())
+                    .unwrap_or("end of file".to_owned());
+
+                Err(Error::new(
+                    ErrorKind::MissingRedirectTarget,
+                    format!("missing redirect target, found {found}"),
+                )
+                .with_span(target_span))
+            }
+            Some(expr) => {
+                self.lexer.consume();
+
+                let mut redirect = Redirect::new(kind, fd, expr);
+
+                redirect.set_span(span.to(&self.lexer.span()));
+
+                Ok(Expression::Redirect(redirect))
+            }
+        }
     }
 
     fn parse_command_prefix(&mut self) -> Result<Expression> {
+        self.expand_aliases();
+
+        let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
         match self.lexer.peek() {
-            None => Err(Error::new(ErrorKind::Unknown, "".to_owned()))?,
+            None => Err(Error::unexpected(expected_word(), None, span))?,
+
+            // true and false are commands too when they start one.
+            Some(Token::True) | Some(Token::False) => {
+                let expr = Expression::String(self.lexer.peek().unwrap().to_string());
+
+                self.lexer.consume();
+
+                Ok(expr)
+            }
 
             Some(token) => {
                 let expr = parse_variable(token).or(parse_string(token).or(parse_number(token)));
 
                 match expr {
-                    Err(err) => Err(err),
+                    Err(_) => Err(Error::unexpected(
+                        expected_word(),
+                        Some(token.clone()),
+                        span,
+                    )),
 
                     Ok(expr) => {
                         self.lexer.consume();
@@ -147,6 +904,138 @@ impl Parser {
             }
         }
     }
+
+    // replaces an unquoted command name that is an alias with the tokens of its
+    // text. the first word of the text is expanded again, unless it is an alias
+    // already being expanded, and when the text ends in a blank so is the word
+    // after it.
+    fn expand_aliases(&mut self) {
+        if self.aliases.is_empty() {
+            return;
+        }
+
+        let mut index = 0;
+
+        let mut expanded = Vec::new();
+
+        // where the word after a text ending in a blank is.
+        let mut next = None;
+
+        loop {
+            let token = self.lexer.nth(index).cloned();
+
+            let alias = match token {
+                Some((Token::String(name), span))
+                    if expanded.contains(&name) == false
+                        && self.lexer.is_quoted(&span) == false =>
+                {
+                    self.aliases
+                        .get(&name)
+                        .map(|text| (name, text.clone(), span))
+                }
+                _ => None,
+            };
+
+            let (name, text, span) = match alias {
+                Some(alias) => alias,
+                None => match next.take() {
+                    Some(word) => {
+                        index = word;
+                        expanded.clear();
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+
+            let tokens = alias_tokens(&text, span);
+
+            let len = tokens.len();
+
+            self.lexer.splice(index, tokens);
+
+            // a word after this one moves by the tokens the alias put in place
+            // of its name, one at the name itself now starts the alias text.
+            next = match text.ends_with([' ', '\t']) {
+                true => Some(index + len),
+                false => next.map(|next: usize| match next > index {
+                    true => next + len - 1,
+                    false => next,
+                }),
+            };
+
+            expanded.push(name);
+        }
+    }
+
+    // the first error the lexer ran into, if any.
+    fn lexer_error(&mut self) -> Result<()> {
+        match self.lexer.take_errors().into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    // consumes the next token if it is of the same kind as token.
+    fn expect(&mut self, token: Token) -> Result<Span> {
+        let span = self.lexer.peek_span().unwrap_or(self.lexer.span());
+
+        if self.lexer.next_is(&token) {
+            self.lexer.consume();
+            return Ok(span);
+        }
+
+        Err(Error::unexpected(
+            vec![token],
+            self.lexer.peek().cloned(),
+            span,
+        ))
+    }
+}
+
+// the tokens of an alias text, placed where the alias name was so errors point
+// at it. words written next to each other in the text stay next to each other.
+fn alias_tokens(text: &str, span: Span) -> Vec<(Token, Span)> {
+    let mut lexer = Lexer::new(text);
+
+    let mut tokens = Vec::new();
+
+    while let Some((token, inner)) = lexer.next_with_span() {
+        if token == Token::EOF {
+            break;
+        }
+
+        let inner = Span::new(
+            span.start() + inner.start(),
+            span.start() + inner.end(),
+            span.line(),
+            span.column() + inner.start(),
+        );
+
+        tokens.push((token, inner));
+    }
+
+    tokens
+}
+
+// tokens that may start a command.
+fn expected_word() -> Vec<Token> {
+    vec![
+        Token::String(String::new()),
+        Token::Ident(String::new()),
+        Token::Number(0),
+    ]
+}
+
+// tokens that may be used as a value.
+fn expected_value() -> Vec<Token> {
+    vec![
+        Token::String(String::new()),
+        Token::Ident(String::new()),
+        Token::Number(0),
+        Token::True,
+        Token::False,
+    ]
 }
 
 // fn next_token_is_eof(lexer: &mut Peekable<Lexer>) -> bool {
@@ -168,28 +1057,40 @@ impl Parser {
 fn parse_fd(token: &Token) -> Result<Expression> {
     match token {
         Token::FD(fd) => Ok(Expression::FD(fd.to_owned())),
-        _ => Err(Error::new(ErrorKind::Unknown, "".to_owned())),
+        _ => Err(Error::new(
+            ErrorKind::UnexpectedToken,
+            format!("unexpected {}", token.name()),
+        )),
     }
 }
 
 fn parse_number(token: &Token) -> Result<Expression> {
     match token {
         Token::Number(number) => Ok(Expression::Number(number.to_owned())),
-        _ => Err(Error::new(ErrorKind::Unknown, "".to_owned())),
+        _ => Err(Error::new(
+            ErrorKind::UnexpectedToken,
+            format!("unexpected {}", token.name()),
+        )),
     }
 }
 
 fn parse_variable(token: &Token) -> Result<Expression> {
     match token {
         Token::Ident(string) => Ok(Expression::Variable(string.to_owned())),
-        _ => Err(Error::new(ErrorKind::Unknown, "".to_owned())),
+        _ => Err(Error::new(
+            ErrorKind::UnexpectedToken,
+            format!("unexpected {}", token.name()),
+        )),
     }
 }
 
 fn parse_string(token: &Token) -> Result<Expression> {
     match token {
         Token::String(string) => Ok(Expression::String(string.to_owned())),
-        _ => Err(Error::new(ErrorKind::Unknown, "".to_owned())),
+        _ => Err(Error::new(
+            ErrorKind::UnexpectedToken,
+            format!("unexpected {}", token.name()),
+        )),
     }
 }
 
@@ -266,3 +1167,87 @@ fn parse_string(token: &Token) -> Result<Expression> {
 //         es_ast::Expression::Command(command)
 //     }};
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> (Block, Vec<Error>) {
+        Parser::new(Lexer::new(source)).parse()
+    }
+
+    #[test]
+    fn ampersand_ends_the_statement() {
+        let (block, errors) = parse("a & b");
+
+        assert!(errors.is_empty());
+        assert_eq!(block.statements().len(), 2);
+    }
+
+    #[test]
+    fn number_against_redirect_is_the_fd() {
+        let (block, errors) = parse("exec 3>file");
+
+        assert!(errors.is_empty());
+        assert_eq!(block.to_string(), "exec &3 > file");
+        assert_eq!(parse("echo 3 > file").0.to_string(), "echo 3 > file");
+    }
+
+    #[test]
+    fn embedded_quote_prints_back_as_written() {
+        let (block, errors) = parse("echo a\"b c\"d");
+
+        assert!(errors.is_empty());
+
+        let printed = block.to_string();
+
+        assert_eq!(printed, "echo a\"b c\"d");
+        assert!(parse(&printed).0 == block);
+    }
+
+    #[test]
+    fn assignment_words_keep_numbers_as_written() {
+        let (block, errors) = parse("export ZIP=02134 N=+5 M=a,007");
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            block.to_string(),
+            "export \"ZIP=02134\" \"N=+5\" \"M=a,007\""
+        );
+    }
+
+    #[test]
+    fn time_is_a_keyword_only_where_a_pipeline_starts() {
+        let (block, errors) = parse("time a | b\n$x = time\nls | time\n\"time\" a");
+
+        assert!(errors.is_empty());
+
+        let statements = block.statements();
+
+        assert!(matches!(
+            &statements[0],
+            Statement::Expression(Expression::Time(_))
+        ));
+        assert!(matches!(&statements[1], Statement::Assign(_)));
+        assert_eq!(statements[2].to_string(), "ls | \"time\"");
+        assert!(matches!(
+            &statements[3],
+            Statement::Expression(Expression::Command(_))
+        ));
+    }
+
+    #[test]
+    fn aliases_may_expand_to_nothing() {
+        let mut parser = Parser::new(Lexer::new("b e x"));
+
+        parser.set_aliases(BTreeMap::from([
+            ("b".to_owned(), " ".to_owned()),
+            ("e".to_owned(), "".to_owned()),
+        ]));
+
+        let (block, errors) = parser.parse();
+
+        assert!(errors.is_empty());
+        assert_eq!(block.to_string(), "x");
+    }
+}
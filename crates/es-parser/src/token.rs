@@ -50,10 +50,29 @@ pub enum Token {
     Ident(String),  // $a , &b
     Number(isize),  // 0 ~ 9
     FD(u32),        // 0 ~ 9
-}
-
 
+    Comment(String), // # comment
+}
 
+impl Token {
+    // human readable name, used in diagnostics.
+    pub fn name(&self) -> String {
+        match self {
+            Token::EOF => "end of file".to_owned(),
+            Token::EOL => "end of line".to_owned(),
+            Token::String(v) if v.is_empty() => "string".to_owned(),
+            Token::String(v) => format!("string `{v}`"),
+            Token::Ident(v) if v.is_empty() => "variable".to_owned(),
+            Token::Ident(v) => format!("variable `${v}`"),
+            Token::Number(_) => "number".to_owned(),
+            Token::FD(_) => "fd".to_owned(),
+            Token::Comment(_) => "comment".to_owned(),
+            Token::LBrace => "`{`".to_owned(),
+            Token::RBrace => "`}`".to_owned(),
+            _ => format!("`{self}`"),
+        }
+    }
+}
 
 impl Display for Token {
     fn fmt(&self, tkn: &mut Formatter) -> Result {
@@ -89,6 +108,7 @@ impl Display for Token {
             Token::Ident(v) => write!(tkn, "{v}"),
             Token::Number(v) => write!(tkn, "{v}"),
             Token::FD(v) => write!(tkn, "{v}"),
+            Token::Comment(v) => write!(tkn, "#{v}"),
         }
     }
 }
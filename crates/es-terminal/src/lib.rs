@@ -1,14 +1,20 @@
 pub mod prompt;
+pub mod raw;
+use raw::RawMode;
 use std::borrow::Cow;
 use std::io;
 use std::io::Write;
-use std::process::exit;
+
+// given the line up to the cursor, the start of the word to complete and the
+// words it can become.
+pub type Completer = Box<dyn Fn(&str) -> (usize, Vec<String>)>;
 
 pub struct Terminal {
     buffer: Vec<u8>,
     buffer_index: usize,
     prompt: String,
-    origin_termios: libc::termios,
+    raw_mode: Option<RawMode>,
+    completer: Option<Completer>,
 }
 
 impl Terminal {
@@ -18,7 +24,8 @@ impl Terminal {
             buffer_index: 0,
 
             prompt: String::new(),
-            origin_termios: termios(),
+            raw_mode: None,
+            completer: None,
         }
     }
 
@@ -26,6 +33,41 @@ impl Terminal {
         self.prompt = string
     }
 
+    // what tab completes, nothing until one is set.
+    pub fn set_completer(&mut self, completer: Completer) {
+        self.completer = Some(completer);
+    }
+
+    // reads lines with the continuation prompt for as long as is_incomplete
+    // reports the input so far as unfinished.
+    pub fn read_lines<F>(&mut self, continuation: String, is_incomplete: F) -> io::Result<String>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut string = self.read_line()?;
+
+        let prompt = self.prompt.clone();
+
+        while is_incomplete(&string) {
+            self.prompt = continuation.clone();
+
+            match self.read_line() {
+                Ok(line) => {
+                    string.push('\n');
+                    string.push_str(&line);
+                }
+                Err(err) => {
+                    self.prompt = prompt;
+                    return Err(err);
+                }
+            }
+        }
+
+        self.prompt = prompt;
+
+        Ok(string)
+    }
+
     pub fn read_line(&mut self) -> io::Result<String> {
         self.set_raw_mode();
 
@@ -39,11 +81,29 @@ impl Terminal {
             if let Some(char) = getch() {
                 match char {
                     0 => continue,
+                    // ctrl-c drops the line, the shell goes on with a new one
                     3 => {
                         self.unset_raw_mode();
-                        exit(0)
+                        stdout.write_all(b"^C\n")?;
+                        stdout.flush()?;
+                        return Err(io::Error::from(io::ErrorKind::Interrupted));
+                    }
+
+                    // ctrl-d on an empty line
+                    4 => {
+                        if self.buffer.is_empty() == false {
+                            continue;
+                        }
+
+                        self.unset_raw_mode();
+                        stdout.write_all(b"\n")?;
+                        stdout.flush()?;
+                        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
                     }
 
+                    // tab
+                    9 => self.complete(&mut stdout)?,
+
                     10 => break,
 
                     27 => {
@@ -152,6 +212,9 @@ impl Terminal {
                         }
                     }
                 }
+            } else {
+                self.unset_raw_mode();
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
             }
         }
 
@@ -162,6 +225,65 @@ impl Terminal {
         Ok(self.get_utf8_str().to_string())
     }
 
+    // completes the word before the cursor as far as its completions agree,
+    // and lists them when they go no further.
+    fn complete(&mut self, stdout: &mut impl Write) -> io::Result<()> {
+        let completer = match &self.completer {
+            Some(completer) => completer,
+            None => return Ok(()),
+        };
+
+        let line = String::from_utf8_lossy(&self.buffer[..self.buffer_index]).into_owned();
+
+        let (start, words) = completer(&line);
+
+        let completion = match words.as_slice() {
+            [] => return Ok(()),
+            [word] => format!("{word} "),
+            [first, rest @ ..] => {
+                let mut prefix = first.as_str();
+
+                for word in rest {
+                    let end = prefix
+                        .char_indices()
+                        .zip(word.chars())
+                        .find(|((_, a), b)| a != b)
+                        .map(|((index, _), _)| index)
+                        .unwrap_or(prefix.len().min(word.len()));
+
+                    prefix = &prefix[..end];
+                }
+
+                prefix.to_owned()
+            }
+        };
+
+        if completion.len() > line.len() - start {
+            self.buffer
+                .splice(start..self.buffer_index, completion.bytes());
+
+            self.buffer_index = start + completion.len();
+        } else {
+            stdout.write_all(format!("\n{}\n", words.join("  ")).as_bytes())?;
+        }
+
+        self.redraw(stdout)
+    }
+
+    // writes the prompt and the line again and puts the cursor back.
+    fn redraw(&self, stdout: &mut impl Write) -> io::Result<()> {
+        stdout.write_all(format!("\r{}{}", self.prompt, self.get_utf8_str()).as_bytes())?;
+
+        if self.buffer_index < self.buffer.len() {
+            let move_position = self.prompt.len() + self.buffer_index + 1;
+
+            stdout
+                .write_all(format!("{}", Cursor::Move(move_position).get_esc_code()).as_bytes())?;
+        }
+
+        Ok(())
+    }
+
     fn get_utf8_str(&self) -> Cow<str> {
         String::from_utf8_lossy(&self.buffer)
     }
@@ -189,26 +311,11 @@ impl Terminal {
     }
 
     fn set_raw_mode(&mut self) {
-        unsafe { libc::tcgetattr(0, &mut self.origin_termios) };
-
-        let mut raw = self.origin_termios;
-
-        raw.c_lflag = raw.c_lflag & !(libc::ICANON | libc::ECHO | libc::IEXTEN | libc::ISIG);
-        // raw.c_lflag = raw.c_lflag & !(libc::ICANON | libc::ECHO );
-        raw.c_cc[libc::VTIME] = 0;
-
-        raw.c_cc[libc::VMIN] = 1;
-
-        unsafe {
-            libc::tcsetattr(0, 0, &raw);
-            libc::fcntl(0, libc::F_SETFL);
-        }
+        self.raw_mode = RawMode::new(0, raw::RAW).ok();
     }
 
     fn unset_raw_mode(&mut self) {
-        unsafe {
-            libc::tcsetattr(0, 0, &self.origin_termios);
-        }
+        self.raw_mode = None;
     }
 }
 
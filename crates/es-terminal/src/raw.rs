@@ -0,0 +1,50 @@
+use std::io;
+use std::os::fd::RawFd;
+
+// local mode flags the line editor turns off: line buffering, echo, extended
+// input processing and signal keys.
+pub const RAW: libc::tcflag_t = libc::ICANON | libc::ECHO | libc::IEXTEN | libc::ISIG;
+
+// a terminal with some local mode flags turned off, they are turned back on
+// when it is dropped.
+pub struct RawMode {
+    fd: RawFd,
+    origin: libc::termios,
+}
+
+impl RawMode {
+    // clears flags, such as RAW or libc::ECHO, on the terminal fd. reads return
+    // as soon as a byte is there.
+    pub fn new(fd: RawFd, flags: libc::tcflag_t) -> io::Result<Self> {
+        let mut origin = crate::termios();
+
+        if unsafe { libc::tcgetattr(fd, &mut origin) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut raw = origin;
+
+        raw.c_lflag &= !flags;
+
+        raw.c_cc[libc::VTIME] = 0;
+
+        raw.c_cc[libc::VMIN] = 1;
+
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            fd: fd,
+            origin: origin,
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(self.fd, libc::TCSANOW, &self.origin);
+        }
+    }
+}
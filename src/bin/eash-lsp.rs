@@ -0,0 +1,15 @@
+use std::io;
+use std::process;
+
+// language server for eash scripts, speaking lsp over stdio.
+fn main() {
+    let stdin = io::stdin();
+
+    match es_lsp::run(stdin.lock(), io::stdout()) {
+        Ok(status) => process::exit(status),
+        Err(err) => {
+            eprintln!("eash-lsp: {err}");
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use es_ast::visit::*;
+use es_ast::*;
+use es_builtin::Registry;
+use es_lint::Lint;
+use es_parser::diagnostic::Diagnostic;
+use es_parser::diagnostic::Severity;
+use es_parser::lexer::Lexer;
+use es_parser::Parser;
+use serde_json::json;
+use serde_json::Value;
+use std::env;
+use std::path::Path;
+
+// eash -n [--json] [-c string | file...]
+// parses without running anything. commands are resolved against the functions
+// of the script, the builtins and PATH, variables against the assignments
+// before them.
+pub fn check(source: &str) -> Vec<Diagnostic> {
+    let (block, errors) = Parser::new(Lexer::new(source)).parse();
+
+    match errors.is_empty() {
+        true => {}
+        false => return errors.iter().map(Diagnostic::from).collect(),
+    }
+
+    let mut resolver = Resolver::default();
+
+    resolver.visit_block(&block);
+
+    let registry = Registry::default();
+
+    let mut diagnostics = es_lint::lint_with(source, &[Lint::UndefinedVariable]);
+
+    for (command, span) in resolver.commands {
+        if resolver.functions.contains(&command) || is_command(&registry, &command) {
+            continue;
+        }
+
+        diagnostics.push(
+            Diagnostic::new(Severity::Warning, format!("command `{command}` not found"))
+                .with_code("unknown-command")
+                .with_span(span)
+                .with_label("not a function, a builtin or a program in PATH".to_owned()),
+        );
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span().map(|span| span.start()));
+
+    diagnostics
+}
+
+pub fn to_json(name: &str, diagnostic: &Diagnostic) -> Value {
+    let span = diagnostic.span().unwrap_or_default();
+
+    json!({
+        "file": name,
+        "severity": diagnostic.severity().to_string(),
+        "code": diagnostic.code(),
+        "message": diagnostic.message(),
+        "label": diagnostic.label(),
+        "notes": diagnostic.notes(),
+        "line": span.line(),
+        "column": span.column(),
+        "start": span.start(),
+        "end": span.end(),
+    })
+}
+
+fn is_command(registry: &Registry, name: &str) -> bool {
+    if registry.contains(name) {
+        return true;
+    }
+
+    if name.contains('/') {
+        return Path::new(name).exists();
+    }
+
+    match env::var_os("PATH") {
+        Some(path) => env::split_paths(&path).any(|dir| dir.join(name).is_file()),
+        None => false,
+    }
+}
+
+// command names with their spans, and the functions defined anywhere.
+#[derive(Default)]
+struct Resolver {
+    functions: Vec<String>,
+    commands: Vec<(String, Span)>,
+}
+
+impl Visitor for Resolver {
+    fn visit_def(&mut self, node: &Def) {
+        self.functions.push(node.name().to_owned());
+
+        walk_def(self, node);
+    }
+
+    fn visit_command(&mut self, node: &Command) {
+        if let Expression::String(name) = node.prefix() {
+            self.commands.push((name.clone(), node.span()));
+        }
+
+        walk_command(self, node);
+    }
+}
@@ -0,0 +1,95 @@
+use es_builtin::Registry;
+use std::collections::BTreeMap;
+
+// what tab completes at the prompt: the builtins and aliases where a command
+// starts, and the options of a builtin after it.
+pub struct Completions {
+    commands: Vec<String>,
+    options: BTreeMap<String, Vec<String>>,
+}
+
+impl Completions {
+    pub fn new(registry: &Registry, aliases: &BTreeMap<String, String>) -> Self {
+        let mut names: Vec<String> = registry
+            .iter()
+            .map(|builtin| builtin.name().to_owned())
+            .chain(aliases.keys().cloned())
+            .collect();
+
+        names.sort();
+
+        names.dedup();
+
+        let mut flags_of = BTreeMap::new();
+
+        for builtin in registry.iter() {
+            let mut flags: Vec<String> = builtin
+                .options()
+                .iter()
+                .map(|(option, _)| option.split(' ').next().unwrap_or(option).to_owned())
+                .collect();
+
+            if builtin.has_help() {
+                flags.push("--help".to_owned());
+            }
+
+            flags_of.insert(builtin.name().to_owned(), flags);
+        }
+
+        Self {
+            commands: names,
+            options: flags_of,
+        }
+    }
+
+    // the start of the word the line ends with and the words it completes to.
+    pub fn complete(&self, line: &str) -> (usize, Vec<String>) {
+        let start = es_parser::word_start(line);
+
+        let word = &line[start..];
+
+        let command = es_parser::command_start(&line[..start]);
+
+        let words = match line[command..start].split_whitespace().next() {
+            None => &self.commands,
+            Some(name) if word.starts_with('-') => match self.options.get(name) {
+                Some(flags) => flags,
+                None => return (start, Vec::new()),
+            },
+            Some(_) => return (start, Vec::new()),
+        };
+
+        let words = words
+            .iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .cloned()
+            .collect();
+
+        (start, words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_commands_and_builtin_options() {
+        let aliases = BTreeMap::from([("exam".to_owned(), "echo".to_owned())]);
+
+        let completions = Completions::new(&Registry::default(), &aliases);
+
+        assert_eq!(
+            completions.complete("echo é; ex"),
+            (
+                9,
+                vec!["exam".into(), "exec".into(), "exit".into(), "export".into()]
+            )
+        );
+        assert_eq!(
+            completions.complete("cd -"),
+            (3, vec!["-L".into(), "-P".into(), "--help".into()])
+        );
+        assert_eq!(completions.complete("echo ex"), (5, Vec::new()));
+    }
+}
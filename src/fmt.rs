@@ -0,0 +1,98 @@
+use es_parser::diagnostic::Diagnostic;
+use std::fs;
+use std::io;
+use std::io::Read;
+
+const USAGE: &str = "usage: eash fmt [--check] [file...]";
+
+// eash fmt [--check] [file...]
+// formats the files in place, or stdin to stdout when no file is given. with
+// --check nothing is written and the exit status is 1 if a file would change.
+pub fn main(args: &[String]) -> i32 {
+    let mut check = false;
+
+    let mut files = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return 0;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("{USAGE}");
+                return 2;
+            }
+            _ => files.push(arg.to_owned()),
+        }
+    }
+
+    if files.is_empty() {
+        let mut source = String::new();
+
+        if let Err(err) = io::stdin().read_to_string(&mut source) {
+            eprintln!("eash: fmt: {err}");
+            return 1;
+        }
+
+        return match format("<stdin>", &source) {
+            None => 1,
+            Some(formatted) if check => (formatted != source) as i32,
+            Some(formatted) => {
+                print!("{formatted}");
+                0
+            }
+        };
+    }
+
+    let mut status = 0;
+
+    for file in files {
+        let source = match fs::read_to_string(&file) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("eash: fmt: {file}: {err}");
+                status = 1;
+                continue;
+            }
+        };
+
+        let formatted = match format(&file, &source) {
+            Some(formatted) => formatted,
+            None => {
+                status = 1;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            println!("{file}");
+            status = 1;
+        } else if let Err(err) = fs::write(&file, formatted) {
+            eprintln!("eash: fmt: {file}: {err}");
+            status = 1;
+        }
+    }
+
+    status
+}
+
+fn format(name: &str, source: &str) -> Option<String> {
+    match es_fmt::format(source) {
+        Ok(formatted) => Some(formatted),
+        Err(errors) => {
+            let color = crate::is_color();
+
+            for err in &errors {
+                eprint!("{}", Diagnostic::from(err).render(name, source, color));
+            }
+
+            None
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use es_lint::Lint;
+use std::fs;
+use std::io;
+use std::io::Read;
+
+const USAGE: &str = "usage: eash lint [--list] [file...]";
+
+// eash lint [--list] [file...]
+// reports the lints of each file, or of stdin when no file is given. the exit
+// status is 1 if anything was reported. --list prints the known lints.
+pub fn main(args: &[String]) -> i32 {
+    let mut files = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--list" => {
+                for lint in Lint::ALL {
+                    println!(
+                        "{:<26}{:<9}{}",
+                        lint.id(),
+                        lint.severity().to_string(),
+                        lint.description()
+                    );
+                }
+                return 0;
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return 0;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("{USAGE}");
+                return 2;
+            }
+            _ => files.push(arg.to_owned()),
+        }
+    }
+
+    if files.is_empty() {
+        let mut source = String::new();
+
+        if let Err(err) = io::stdin().read_to_string(&mut source) {
+            eprintln!("eash: lint: {err}");
+            return 1;
+        }
+
+        return lint("<stdin>", &source) as i32;
+    }
+
+    let mut status = 0;
+
+    for file in files {
+        match fs::read_to_string(&file) {
+            Ok(source) => {
+                if lint(&file, &source) {
+                    status = 1;
+                }
+            }
+            Err(err) => {
+                eprintln!("eash: lint: {file}: {err}");
+                status = 1;
+            }
+        }
+    }
+
+    status
+}
+
+// returns true when something was reported.
+fn lint(name: &str, source: &str) -> bool {
+    let color = crate::is_color();
+
+    let mut is_reported = false;
+
+    for diagnostic in es_lint::lint(source) {
+        eprint!("{}", diagnostic.render(name, source, color));
+
+        is_reported = true;
+    }
+
+    is_reported
+}
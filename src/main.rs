@@ -1 +1,306 @@
-fn main() {}
+mod check;
+mod complete;
+mod fmt;
+mod lint;
+
+use es_ast::Block;
+use es_builtin::Registry;
+use es_builtin::State;
+use es_engine::Evaluator;
+use es_manifest as manifest;
+use es_parser::diagnostic::Diagnostic;
+use es_parser::diagnostic::Severity;
+use es_parser::error::Error;
+use es_parser::lexer::Lexer;
+use es_parser::Parser;
+use es_terminal::prompt;
+use es_terminal::Terminal;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::process;
+
+const DEFAULT_PS1: &str = "\\s-\\v$ ";
+
+const DEFAULT_PS2: &str = "> ";
+
+const USAGE: &str =
+    "usage: eash [--dump-ast] [-c string | file] [arg...]\n       eash -n [--json] [-c string | file...]\n       eash fmt [--check] [file...]\n       eash lint [--list] [file...]";
+
+fn main() {
+    let argv = env::args().skip(1).collect::<Vec<String>>();
+
+    match argv.first().map(|arg| arg.as_str()) {
+        Some("fmt") => process::exit(fmt::main(&argv[1..])),
+        Some("lint") => process::exit(lint::main(&argv[1..])),
+        _ => {}
+    }
+
+    let mut dump_ast = false;
+
+    let mut check = false;
+
+    let mut json = false;
+
+    let mut command = None;
+
+    let mut files = Vec::new();
+
+    // positional parameters of the script.
+    let mut script_args = Vec::new();
+
+    let mut args = argv.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dump-ast" => dump_ast = true,
+
+            "-n" | "--check" => check = true,
+
+            "--json" => json = true,
+
+            "-c" => match args.next() {
+                Some(string) => {
+                    command = Some(string);
+
+                    // -n checks every file, otherwise the rest are arguments.
+                    if check {
+                        continue;
+                    }
+
+                    script_args.extend(args.by_ref());
+                }
+                None => usage(),
+            },
+
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+
+            _ => {
+                if arg.starts_with('-') {
+                    usage();
+                }
+
+                files.push(arg);
+
+                if check {
+                    continue;
+                }
+
+                script_args.extend(args.by_ref());
+            }
+        }
+    }
+
+    if check {
+        process::exit(check_sources(command, files, json));
+    }
+
+    // --json only goes with -n.
+    if json {
+        usage();
+    }
+
+    let (name, source) = match (command, files.pop()) {
+        (Some(command), _) => ("-c".to_owned(), command),
+        (None, Some(file)) => match fs::read_to_string(&file) {
+            Ok(source) => (file, source),
+            Err(err) => {
+                eprintln!("eash: {file}: {err}");
+                process::exit(127);
+            }
+        },
+        (None, None) => return repl(),
+    };
+
+    if dump_ast {
+        process::exit(dump(&name, &source));
+    }
+
+    let script_name = match name.as_str() {
+        "-c" => manifest::name(),
+        _ => name.clone(),
+    };
+
+    let mut evaluator = Evaluator::new(State::new(script_name, script_args), Registry::default());
+
+    evaluator.catch_signals();
+
+    let status = run(&mut evaluator, &name, &source);
+
+    process::exit(evaluator.exit_trap(status));
+}
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    process::exit(2)
+}
+
+// syntax check of each source, 1 if any has an error.
+fn check_sources(command: Option<String>, files: Vec<String>, json: bool) -> i32 {
+    let mut sources = Vec::new();
+
+    let mut status = 0;
+
+    match command {
+        Some(command) => sources.push(("-c".to_owned(), command)),
+        None if files.is_empty() => usage(),
+        None => {
+            for file in files {
+                match fs::read_to_string(&file) {
+                    Ok(source) => sources.push((file, source)),
+                    Err(err) => {
+                        eprintln!("eash: {file}: {err}");
+                        status = 1;
+                    }
+                }
+            }
+        }
+    }
+
+    let color = is_color();
+
+    let mut report = Vec::new();
+
+    for (name, source) in &sources {
+        for diagnostic in check::check(source) {
+            if diagnostic.severity() == Severity::Error {
+                status = 1;
+            }
+
+            match json {
+                true => report.push(check::to_json(name, &diagnostic)),
+                false => eprint!("{}", diagnostic.render(name, source, color)),
+            }
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::Value::Array(report));
+    }
+
+    status
+}
+
+fn is_color() -> bool {
+    unsafe { libc::isatty(2) == 1 }
+}
+
+// parses the source, expanding aliases, and prints its syntax errors. None
+// when there are any.
+fn parse(name: &str, source: &str, aliases: &BTreeMap<String, String>) -> Option<Block> {
+    let mut parser = Parser::new(Lexer::new(source));
+
+    parser.set_aliases(aliases.clone());
+
+    let (block, errors) = parser.parse();
+
+    report(name, source, &errors);
+
+    match errors.is_empty() {
+        true => Some(block),
+        false => None,
+    }
+}
+
+fn report(name: &str, source: &str, errors: &[Error]) {
+    let color = is_color();
+
+    for err in errors {
+        eprint!("{}", Diagnostic::from(err).render(name, source, color));
+    }
+}
+
+// prints the syntax tree as json, without running anything.
+fn dump(name: &str, source: &str) -> i32 {
+    let block = match parse(name, source, &BTreeMap::new()) {
+        Some(block) => block,
+        None => return 2,
+    };
+
+    match serde_json::to_string_pretty(&block) {
+        Ok(json) => {
+            println!("{json}");
+            0
+        }
+        Err(err) => {
+            eprintln!("eash: {err}");
+            1
+        }
+    }
+}
+
+// runs the source and returns its exit status, 2 on syntax errors.
+fn run(evaluator: &mut Evaluator, name: &str, source: &str) -> i32 {
+    let block = match parse(name, source, evaluator.state().aliases()) {
+        Some(block) => block,
+        None => return 2,
+    };
+
+    // an alias takes effect on the lines after the one defining it.
+    match evaluator.eval_script(block, name, source) {
+        Ok(status) => status,
+        Err(errors) => {
+            report(name, source, &errors);
+            2
+        }
+    }
+}
+
+fn repl() {
+    let mut terminal = Terminal::new();
+
+    let mut evaluator = Evaluator::new(
+        State::new(manifest::name(), Vec::new()),
+        Registry::default(),
+    );
+
+    evaluator.set_interactive(true);
+
+    evaluator.catch_signals();
+
+    loop {
+        terminal.prompt(prompt::parse(
+            &env::var("PS1").unwrap_or(DEFAULT_PS1.to_owned()),
+        ));
+
+        let ps2 = prompt::parse(&env::var("PS2").unwrap_or(DEFAULT_PS2.to_owned()));
+
+        // builtins and aliases may have changed since the last line.
+        let completions =
+            complete::Completions::new(evaluator.registry(), evaluator.state().aliases());
+
+        terminal.set_completer(Box::new(move |line| completions.complete(line)));
+
+        let source = match terminal.read_lines(ps2, es_parser::is_incomplete) {
+            Ok(source) => source,
+            // ctrl-c drops the line being edited.
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                evaluator.state_mut().set_status(130);
+                continue;
+            }
+            Err(_) => break,
+        };
+
+        let status = run(&mut evaluator, "<stdin>", &source);
+
+        evaluator.state_mut().set_status(status);
+
+        if let Some(code) = evaluator.state().exit_code() {
+            process::exit(evaluator.exit_trap(code));
+        }
+    }
+
+    // a hangup ends the read as well, its jobs get it before the shell exits.
+    evaluator.handle_signals();
+
+    let status = evaluator
+        .state()
+        .exit_code()
+        .unwrap_or(evaluator.state().status());
+
+    process::exit(evaluator.exit_trap(status));
+}
@@ -0,0 +1,105 @@
+use std::process::Command;
+
+// runs source with eash -c and returns its stdout and exit status.
+fn eash(source: &str) -> (String, i32) {
+    let output = Command::new(env!("CARGO_BIN_EXE_eash"))
+        .arg("-c")
+        .arg(source)
+        .output()
+        .unwrap();
+
+    (
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        output.status.code().unwrap_or(-1),
+    )
+}
+
+#[test]
+fn command_and_builtin_skip_functions() {
+    let (stdout, _) =
+        eash("def echo { printf \"function\\n\" }\necho x\ncommand echo x\nbuiltin echo x");
+
+    assert_eq!(stdout, "function\nx\nx\n");
+}
+
+#[test]
+fn nounset_stops_a_script() {
+    let (stdout, status) = eash("set -u\necho $nope\necho after");
+
+    assert_eq!(stdout, "");
+    assert_eq!(status, 1);
+}
+
+#[test]
+fn exec_opens_an_fd_for_the_shell() {
+    let path = std::env::temp_dir().join(format!("eash-exec-{}", std::process::id()));
+
+    let (stdout, _) = eash(&format!(
+        "exec 3>{}\necho one >&3\necho done",
+        path.display()
+    ));
+
+    assert_eq!(stdout, "done\n");
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\n");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn failed_exec_keeps_the_redirections_of_the_shell() {
+    let (stdout, _) = eash("exec nosuchcommand > /dev/null\necho after $?");
+
+    assert_eq!(stdout, "after 127\n");
+}
+
+#[test]
+fn exec_in_a_pipeline_leaves_the_shell_alone() {
+    let (stdout, _) = eash("exec > /dev/null | cat\necho hi | exec cat\necho after");
+
+    assert_eq!(stdout, "hi\nafter\n");
+}
+
+#[test]
+fn script_aliases_apply_to_later_lines() {
+    let path = std::env::temp_dir().join(format!("eash-alias-{}", std::process::id()));
+
+    std::fs::write(&path, "alias hi=\"echo hello\"\nhi there\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_eash"))
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "hello there\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn runtime_errors_name_the_script_and_line() {
+    let output = Command::new(env!("CARGO_BIN_EXE_eash"))
+        .arg("-c")
+        .arg("echo one\nnosuchcommand")
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "eash: -c: line 2: nosuchcommand: command not found\n"
+    );
+}
+
+#[test]
+fn jobs_keep_their_number_after_an_earlier_one_ends() {
+    let (stdout, _) = eash("true &\nsleep 5 &\nsleep 0.2\nkill %2\necho $?\nwait $!\necho $?");
+
+    assert_eq!(stdout, "0\n143\n");
+}
+
+#[test]
+fn help_is_an_operand_of_echo_and_test() {
+    let (stdout, _) = eash("echo --help\ntest --help\necho $?\n[ --help ]\necho $?");
+
+    assert_eq!(stdout, "--help\n0\n0\n");
+}